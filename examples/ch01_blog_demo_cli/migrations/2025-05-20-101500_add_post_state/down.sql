@@ -0,0 +1,10 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX posts_scheduled_publish_at_idx;
+
+ALTER TABLE posts
+    DROP CONSTRAINT posts_scheduled_has_publish_at,
+    DROP CONSTRAINT posts_published_matches_state,
+    DROP COLUMN publish_at,
+    DROP COLUMN state;
+
+DROP TYPE post_state;
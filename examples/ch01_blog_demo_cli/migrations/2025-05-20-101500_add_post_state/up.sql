@@ -0,0 +1,16 @@
+-- Your SQL goes here
+CREATE TYPE post_state AS ENUM ('draft', 'scheduled', 'published', 'archived');
+
+ALTER TABLE posts
+    ADD COLUMN state post_state NOT NULL DEFAULT 'draft',
+    ADD COLUMN publish_at TIMESTAMPTZ;
+
+UPDATE posts SET state = 'published' WHERE published;
+
+-- published 保留为"是否公开"的快捷字段，必须与 state 保持一致
+ALTER TABLE posts
+    ADD CONSTRAINT posts_published_matches_state CHECK (published = (state = 'published')),
+    ADD CONSTRAINT posts_scheduled_has_publish_at CHECK (state <> 'scheduled' OR publish_at IS NOT NULL);
+
+-- 发布任务只扫描到期的定时文章
+CREATE INDEX posts_scheduled_publish_at_idx ON posts (publish_at) WHERE state = 'scheduled';
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX posts_search_vector_idx;
+
+ALTER TABLE posts DROP COLUMN search_vector;
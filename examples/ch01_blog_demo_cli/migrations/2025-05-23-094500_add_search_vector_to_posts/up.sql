@@ -0,0 +1,8 @@
+-- Your SQL goes here
+-- 标题权重高于正文；使用 simple 配置，不做词干提取，中英文混排时按空白与标点切分
+ALTER TABLE posts
+    ADD COLUMN search_vector TSVECTOR NOT NULL GENERATED ALWAYS AS (
+        setweight(to_tsvector('simple', title), 'A') || setweight(to_tsvector('simple', body), 'B')
+    ) STORED;
+
+CREATE INDEX posts_search_vector_idx ON posts USING GIN (search_vector);
@@ -0,0 +1,15 @@
+-- Your SQL goes here
+-- 标签名统一保存为去除首尾空白的小写形式
+CREATE TABLE tags (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR NOT NULL UNIQUE CHECK (name <> '' AND name = lower(btrim(name)))
+);
+
+CREATE TABLE post_tags (
+    post_id INTEGER NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    tag_id INTEGER NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    PRIMARY KEY (post_id, tag_id)
+);
+
+-- 主键只能加速按文章查标签，按标签查文章需要单独的索引
+CREATE INDEX post_tags_tag_id_idx ON post_tags (tag_id);
@@ -0,0 +1,6 @@
+-- Your SQL goes here
+CREATE TYPE body_format AS ENUM ('markdown', 'plain');
+
+-- 已有文章都是纯文本，新文章默认使用 markdown
+ALTER TABLE posts ADD COLUMN body_format body_format NOT NULL DEFAULT 'plain';
+ALTER TABLE posts ALTER COLUMN body_format SET DEFAULT 'markdown';
@@ -0,0 +1,17 @@
+-- Your SQL goes here
+-- 已有文章无法在 SQL 中音译标题，先使用 post-<id> 作为 slug
+ALTER TABLE posts ADD COLUMN slug VARCHAR;
+UPDATE posts SET slug = 'post-' || id;
+ALTER TABLE posts
+    ALTER COLUMN slug SET NOT NULL,
+    ADD CONSTRAINT posts_slug_key UNIQUE (slug),
+    ADD CONSTRAINT posts_slug_format CHECK (slug ~ '^[a-z0-9]+(-[a-z0-9]+)*$');
+
+-- 标题修改后旧的 slug 仍然可以访问，重定向到文章当前的 slug
+CREATE TABLE post_slug_redirects (
+    old_slug VARCHAR PRIMARY KEY,
+    post_id INTEGER NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX post_slug_redirects_post_id_idx ON post_slug_redirects (post_id);
@@ -0,0 +1,19 @@
+-- Your SQL goes here
+CREATE TYPE comment_status AS ENUM ('pending', 'approved', 'spam');
+
+CREATE TABLE comments (
+    id SERIAL PRIMARY KEY,
+    post_id INTEGER NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    -- 回复的评论，顶层评论为 NULL
+    parent_id INTEGER,
+    author VARCHAR NOT NULL,
+    body TEXT NOT NULL,
+    status comment_status NOT NULL DEFAULT 'pending',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (id, post_id),
+    -- 同时引用 post_id，保证回复与被回复的评论属于同一篇文章
+    FOREIGN KEY (parent_id, post_id) REFERENCES comments (id, post_id) ON DELETE CASCADE
+);
+
+CREATE INDEX comments_post_id_idx ON comments (post_id);
+CREATE INDEX comments_parent_id_idx ON comments (parent_id);
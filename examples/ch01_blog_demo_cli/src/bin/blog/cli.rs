@@ -0,0 +1,233 @@
+use ch01_blog_demo_cli::body_format::BodyFormat;
+use ch01_blog_demo_cli::comment_status::CommentStatus;
+use ch01_blog_demo_cli::posts::SortKey;
+use ch01_blog_demo_cli::transfer::OnConflict;
+use common::parse_age;
+use chrono::{DateTime, TimeDelta, Utc};
+use clap::{ArgGroup, Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(name = "blog", about = "博客文章命令行工具")]
+pub struct Cli {
+    /// 输出格式
+    #[arg(long, value_enum, global = true, default_value_t = Format::Table)]
+    pub format: Format,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Format {
+    Table,
+    Json,
+    /// 每行一个 JSON 对象
+    Ndjson,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// 创建文章
+    Create {
+        #[arg(long)]
+        title: String,
+        #[arg(long)]
+        body: String,
+        /// 正文格式
+        #[arg(long, value_enum, default_value_t = BodyFormat::Markdown)]
+        body_format: BodyFormat,
+        /// 创建后立即发布
+        #[arg(long)]
+        publish: bool,
+    },
+    /// 按 id 或 slug 查询单篇文章
+    Get {
+        /// 文章 id 或 slug
+        key: String,
+    },
+    /// 修改文章标题，旧的 slug 会重定向到新的 slug
+    Rename {
+        id: i32,
+        #[arg(long)]
+        title: String,
+    },
+    /// 列出文章，默认只展示已发布的文章
+    List(ListArgs),
+    /// 把文章渲染为 HTML 输出
+    Render { id: i32 },
+    /// 导出文章
+    Export {
+        #[command(subcommand)]
+        target: ExportTarget,
+    },
+    /// 从 export posts 导出的 JSON 或 NDJSON 文件导入文章
+    Import {
+        /// 输入文件，`-` 表示标准输入
+        file: PathBuf,
+        /// 文章 id 已存在时的处理方式
+        #[arg(long, value_enum, default_value_t = OnConflict::Fail)]
+        on_conflict: OnConflict,
+    },
+    /// 发表评论，新评论需要审核后才会公开
+    Comment {
+        post_id: i32,
+        #[arg(long)]
+        author: String,
+        #[arg(long)]
+        body: String,
+        /// 回复的评论 id
+        #[arg(long, value_name = "COMMENT_ID")]
+        reply_to: Option<i32>,
+    },
+    /// 查看文章及其评论
+    Comments {
+        post_id: i32,
+        /// 同时展示待审核与垃圾评论
+        #[arg(long)]
+        all: bool,
+    },
+    /// 修改评论的审核状态
+    Moderate {
+        comment_id: i32,
+        #[arg(value_enum)]
+        status: CommentStatus,
+    },
+    /// 全文搜索文章标题与正文，按相关度排序
+    Search {
+        query: String,
+        /// 同时搜索未发布的文章
+        #[arg(long)]
+        all: bool,
+        #[arg(long, default_value_t = 10)]
+        limit: i64,
+    },
+    /// 为文章添加标签
+    Tag {
+        id: i32,
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+    /// 移除文章的标签
+    Untag {
+        id: i32,
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+    /// 列出带有指定标签的文章，默认要求同时带有全部标签
+    Tagged {
+        #[arg(required = true)]
+        tags: Vec<String>,
+        /// 带有任意一个标签即可
+        #[arg(long)]
+        any: bool,
+        /// 同时展示未发布的文章
+        #[arg(long)]
+        all: bool,
+    },
+    /// 标签云：每个标签下已发布文章的数量
+    Tags,
+    /// 发布文章
+    Publish { id: i32 },
+    /// 取消发布文章
+    Unpublish { id: i32 },
+    /// 定时发布文章，由 publish_worker 在到期后发布
+    Schedule(ScheduleArgs),
+    /// 归档文章
+    Archive { id: i32 },
+    /// 根据标题模糊删除文章
+    Delete {
+        #[arg(long)]
+        title_like: String,
+        /// 跳过删除确认
+        #[arg(long)]
+        yes: bool,
+        /// 只预览将被删除的文章，不执行删除
+        #[arg(long)]
+        dry_run: bool,
+        /// 匹配的文章超过该数量时需要确认，默认每次删除都需要确认
+        #[arg(long, value_name = "N", default_value_t = 0)]
+        confirm_above: usize,
+    },
+    /// 在终端界面中浏览、编辑文章
+    Tui {
+        /// 使用内存中的示例数据，不连接数据库
+        #[arg(long)]
+        demo: bool,
+        /// 每页展示的文章数
+        #[arg(long, default_value_t = 20, value_parser = clap::value_parser!(i64).range(1..=500))]
+        per_page: i64,
+    },
+    /// 管理内嵌的数据库迁移，默认执行所有未执行的迁移
+    Migrate {
+        #[command(subcommand)]
+        action: Option<MigrateAction>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ExportTarget {
+    /// 把已发布的文章导出为静态站点
+    Site {
+        /// 输出目录，不存在时自动创建
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// 以 JSON 或 NDJSON 格式导出全部文章，默认 NDJSON
+    Posts {
+        /// 输出文件，默认输出到标准输出
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// 改用服务端游标读取，每次 FETCH 的行数
+        #[arg(long, value_parser = clap::value_parser!(i64).range(1..))]
+        fetch_size: Option<i64>,
+    },
+}
+
+#[derive(Subcommand, Clone, Copy)]
+pub enum MigrateAction {
+    /// 执行所有未执行的迁移
+    Up,
+    /// 回滚最近执行的迁移
+    Down,
+    /// 查看迁移状态
+    Status,
+    /// 回滚并重新执行最近的迁移
+    Redo,
+}
+
+#[derive(Args)]
+#[command(group(ArgGroup::new("when").required(true).args(["at", "delay"])))]
+pub struct ScheduleArgs {
+    pub id: i32,
+    /// 发布时间，RFC 3339 格式，如 2025-06-01T08:00:00Z
+    #[arg(long)]
+    pub at: Option<DateTime<Utc>>,
+    /// 在多久之后发布，如 30m、12h、7d
+    #[arg(long = "in", value_name = "AGE", value_parser = parse_age)]
+    pub delay: Option<TimeDelta>,
+}
+
+#[derive(Args)]
+pub struct ListArgs {
+    /// 展示全部文章
+    #[arg(long, conflicts_with = "drafts")]
+    pub all: bool,
+    /// 只展示草稿
+    #[arg(long)]
+    pub drafts: bool,
+    #[arg(long, default_value_t = 5)]
+    pub limit: i64,
+    /// 排序字段
+    #[arg(long, value_enum, default_value_t = SortKey::Id)]
+    pub sort: SortKey,
+    /// 按降序排列
+    #[arg(long)]
+    pub desc: bool,
+    /// 只展示最近一段时间内创建的文章，如 7d、12h
+    #[arg(long, value_name = "AGE", value_parser = parse_age)]
+    pub created_within: Option<TimeDelta>,
+    /// 只展示最近一段时间内更新过的文章，如 7d、12h
+    #[arg(long, value_name = "AGE", value_parser = parse_age)]
+    pub updated_within: Option<TimeDelta>,
+}
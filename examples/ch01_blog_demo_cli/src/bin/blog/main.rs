@@ -0,0 +1,269 @@
+use ch01_blog_demo_cli::comments;
+use ch01_blog_demo_cli::models::{NewComment, NewPost};
+use ch01_blog_demo_cli::posts::{self, ListOptions, Lookup, Visibility};
+use ch01_blog_demo_cli::render;
+use ch01_blog_demo_cli::search;
+use ch01_blog_demo_cli::tags::{self, TagMatch};
+use ch01_blog_demo_cli::transfer::{self, DumpFormat};
+use ch01_blog_demo_cli::tui::store::{DbStore, MemoryStore};
+use ch01_blog_demo_cli::{try_establish_connection, AppError, MIGRATIONS, SCHEMA};
+use common::migrations;
+use chrono::Utc;
+use clap::Parser;
+use diesel::PgConnection;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Write};
+use std::process::ExitCode;
+
+mod cli;
+mod output;
+mod tui;
+
+use cli::{Cli, Command, ExportTarget, Format, MigrateAction};
+
+/// 删除前预览展示的文章数量
+const PREVIEW_SAMPLE_SIZE: usize = 5;
+
+struct DeleteOptions {
+    yes: bool,
+    dry_run: bool,
+    confirm_above: usize,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    // 设置 DIESEL_LOG=debug 时在标准错误输出执行的 SQL
+    common::query_log::init_tracing();
+
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("错误: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    // migrate 子命令自行管理迁移，其余命令在启动时自动执行迁移
+    if let Command::Migrate { action } = cli.command {
+        let conn = &mut common::try_establish_schema_connection(SCHEMA)?;
+        return migrate(conn, action.unwrap_or(MigrateAction::Up));
+    }
+
+    if let Command::Tui { demo, per_page } = cli.command {
+        return if demo {
+            tui::run(MemoryStore::with_sample_posts(45), per_page)
+        } else {
+            tui::run(DbStore::new(try_establish_connection()?), per_page)
+        };
+    }
+
+    let conn = &mut try_establish_connection()?;
+
+    match cli.command {
+        Command::Create { title, body, body_format, publish } => {
+            let post = posts::create_post(conn, &NewPost { title, body, body_format }, publish)?;
+            output::print_post(&post, cli.format)?;
+        }
+        Command::Get { key } => {
+            let post = match posts::lookup_post(conn, &key)?.ok_or_else(|| not_found(&key))? {
+                Lookup::Found(post) => post,
+                Lookup::Redirect(post) => {
+                    eprintln!("{} 已重定向到 {}", key, post.slug);
+                    post
+                }
+            };
+            output::print_post(&post, cli.format)?;
+        }
+        Command::Rename { id, title } => {
+            let post = posts::rename_post(conn, id, &title)?.ok_or_else(|| not_found(id))?;
+            output::print_post(&post, cli.format)?;
+        }
+        Command::List(args) => {
+            let visibility = if args.all {
+                Visibility::All
+            } else if args.drafts {
+                Visibility::Drafts
+            } else {
+                Visibility::Published
+            };
+            let now = Utc::now();
+            let options = ListOptions {
+                visibility,
+                sort: args.sort,
+                descending: args.desc,
+                created_after: args.created_within.map(|age| now - age),
+                updated_after: args.updated_within.map(|age| now - age),
+                limit: args.limit,
+            };
+            let results = posts::list_posts(conn, &options)?;
+            let ids: Vec<i32> = results.iter().map(|post| post.id).collect();
+            let comment_counts = comments::approved_counts(conn, &ids)?;
+            output::print_posts(&results, &comment_counts, cli.format)?;
+        }
+        Command::Render { id } => {
+            let post = posts::find_post(conn, id)?.ok_or_else(|| not_found(id))?;
+            print!("{}", render::render_post(&post));
+        }
+        Command::Export { target: ExportTarget::Site { out } } => {
+            let exported = render::export_site(conn, &out)?;
+            println!("已导出 {} 篇文章到 {}", exported, out.display());
+        }
+        Command::Export { target: ExportTarget::Posts { out, fetch_size } } => {
+            let format = match cli.format {
+                Format::Json => DumpFormat::Json,
+                Format::Table | Format::Ndjson => DumpFormat::Ndjson,
+            };
+            match out {
+                Some(path) => {
+                    let exported = transfer::export_posts(conn, &mut BufWriter::new(File::create(&path)?), format, fetch_size)?;
+                    eprintln!("已导出 {} 篇文章到 {}", exported, path.display());
+                }
+                None => {
+                    transfer::export_posts(conn, &mut io::stdout().lock(), format, fetch_size)?;
+                }
+            }
+        }
+        Command::Import { file, on_conflict } => {
+            let input = if file.as_os_str() == "-" {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input)?;
+                input
+            } else {
+                fs::read_to_string(&file)?
+            };
+            let summary = transfer::import_posts(conn, &input, on_conflict)?;
+            println!("新增 {} 篇，覆盖 {} 篇，跳过 {} 篇", summary.inserted, summary.updated, summary.skipped);
+        }
+        Command::Comment { post_id, author, body, reply_to } => {
+            let new_comment = NewComment { post_id, parent_id: reply_to, author, body };
+            let comment = comments::add_comment(conn, &new_comment)?;
+            println!("评论 {} 已提交，等待审核", comment.id);
+        }
+        Command::Comments { post_id, all } => {
+            let thread = comments::load_thread(conn, post_id, all)?.ok_or_else(|| not_found(post_id))?;
+            output::print_thread(&thread, cli.format)?;
+        }
+        Command::Moderate { comment_id, status } => {
+            let comment = comments::moderate_comment(conn, comment_id, status)?
+                .ok_or_else(|| AppError::NotFound(format!("评论 {}", comment_id)))?;
+            println!("评论 {} 已标记为 {}", comment.id, comment.status);
+        }
+        Command::Search { query, all, limit } => {
+            let hits = search::search_posts(conn, &query, all, limit)?;
+            output::print_hits(&hits, cli.format)?;
+        }
+        Command::Tag { id, tags } => {
+            let tags = tags::tag_post(conn, id, &tags)?;
+            output::print_tags(&tags, cli.format)?;
+        }
+        Command::Untag { id, tags } => {
+            let tags = tags::untag_post(conn, id, &tags)?;
+            output::print_tags(&tags, cli.format)?;
+        }
+        Command::Tagged { tags, any, all } => {
+            let tag_match = if any { TagMatch::Any } else { TagMatch::All };
+            let results = tags::posts_with_tags(conn, &tags, tag_match, all)?;
+            output::print_tagged_posts(&results, cli.format)?;
+        }
+        Command::Tags => {
+            let cloud = tags::tag_cloud(conn)?;
+            output::print_tag_cloud(&cloud, cli.format)?;
+        }
+        Command::Publish { id } => {
+            let post = posts::set_published(conn, id, true)?.ok_or_else(|| not_found(id))?;
+            output::print_post(&post, cli.format)?;
+        }
+        Command::Unpublish { id } => {
+            let post = posts::set_published(conn, id, false)?.ok_or_else(|| not_found(id))?;
+            output::print_post(&post, cli.format)?;
+        }
+        Command::Schedule(args) => {
+            let publish_at = match (args.at, args.delay) {
+                (Some(at), _) => at,
+                (None, Some(delay)) => Utc::now() + delay,
+                (None, None) => unreachable!("clap 保证 --at 与 --in 至少提供一个"),
+            };
+            if publish_at <= Utc::now() {
+                return Err(AppError::Validation("定时发布时间必须晚于当前时间".into()).into());
+            }
+            let post = posts::schedule_post(conn, args.id, publish_at)?.ok_or_else(|| not_found(args.id))?;
+            output::print_post(&post, cli.format)?;
+        }
+        Command::Archive { id } => {
+            let post = posts::archive_post(conn, id)?.ok_or_else(|| not_found(id))?;
+            output::print_post(&post, cli.format)?;
+        }
+        Command::Delete { title_like, yes, dry_run, confirm_above } => {
+            delete(conn, &title_like, DeleteOptions { yes, dry_run, confirm_above })?
+        }
+        Command::Migrate { .. } | Command::Tui { .. } => unreachable!("migrate 与 tui 已在连接前处理"),
+    }
+
+    Ok(())
+}
+
+fn delete(conn: &mut PgConnection, pattern: &str, options: DeleteOptions) -> Result<(), Box<dyn Error>> {
+    let preview = posts::preview_delete_title_like(conn, pattern, PREVIEW_SAMPLE_SIZE)?;
+    if preview.affected == 0 {
+        println!("没有标题包含 \"{}\" 的帖子", pattern);
+        return Ok(());
+    }
+
+    output::print_preview(&preview);
+    if options.dry_run {
+        return Ok(());
+    }
+
+    let needs_confirm = preview.affected > options.confirm_above && !options.yes;
+    if needs_confirm && !confirm(&format!("将删除 {} 篇帖子，是否继续？[y/N] ", preview.affected))? {
+        println!("已取消");
+        return Ok(());
+    }
+
+    let num_deleted = posts::delete_title_like(conn, pattern)?;
+    println!("删除 {} 篇帖子", num_deleted);
+    Ok(())
+}
+
+fn migrate(conn: &mut PgConnection, action: MigrateAction) -> Result<(), Box<dyn Error>> {
+    match action {
+        MigrateAction::Up => {
+            let executed = migrations::run_pending(conn, &MIGRATIONS)?;
+            if executed.is_empty() {
+                println!("没有需要执行的迁移");
+            }
+            for name in executed {
+                println!("已执行 {}", name);
+            }
+        }
+        MigrateAction::Down => println!("已回滚 {}", migrations::revert_last(conn, &MIGRATIONS)?),
+        MigrateAction::Redo => println!("已重做 {}", migrations::redo(conn, &MIGRATIONS)?),
+        MigrateAction::Status => {
+            let report = migrations::status(conn, &MIGRATIONS)?;
+            for migration in report.migrations {
+                let mark = if migration.applied { "[X]" } else { "[ ]" };
+                println!("{} {}", mark, migration.name);
+            }
+            for version in &report.unknown {
+                println!("[?] {} (当前程序未知)", version);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn confirm(prompt: &str) -> io::Result<bool> {
+    print!("{}", prompt);
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+fn not_found(key: impl std::fmt::Display) -> AppError {
+    AppError::NotFound(format!("文章 {}", key))
+}
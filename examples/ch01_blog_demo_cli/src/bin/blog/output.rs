@@ -0,0 +1,191 @@
+use ch01_blog_demo_cli::comments::{CommentNode, PostThread};
+use ch01_blog_demo_cli::models::{Post, Tag};
+use ch01_blog_demo_cli::search::SearchHit;
+use ch01_blog_demo_cli::tags::PostWithTags;
+use common::dry_run::Preview;
+use serde::Serialize;
+use std::collections::HashMap;
+use unicode_width::UnicodeWidthStr;
+
+use crate::cli::Format;
+
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+pub fn print_post(post: &Post, format: Format) -> serde_json::Result<()> {
+    match format {
+        Format::Table => {
+            println!("文章ID: {}", post.id);
+            println!("标题: {}", post.title);
+            println!("Slug: {}", post.slug);
+            println!("状态: {}", post.state);
+            println!("正文格式: {}", post.body_format);
+            if let Some(publish_at) = post.publish_at {
+                println!("定时发布: {}", publish_at.format(TIME_FORMAT));
+            }
+            println!("创建时间: {}", post.created_at.format(TIME_FORMAT));
+            println!("更新时间: {}", post.updated_at.format(TIME_FORMAT));
+            println!("内容: {}", post.body);
+            Ok(())
+        }
+        format => print_json(post, format),
+    }
+}
+
+/// 列表中的一篇文章，JSON 输出时附带评论数
+#[derive(Serialize)]
+struct ListedPost<'a> {
+    #[serde(flatten)]
+    post: &'a Post,
+    comments: i64,
+}
+
+pub fn print_posts(posts: &[Post], comment_counts: &HashMap<i32, i64>, format: Format) -> serde_json::Result<()> {
+    let count_of = |post: &Post| comment_counts.get(&post.id).copied().unwrap_or(0);
+    match format {
+        Format::Table => {
+            println!("{} {} {} {} 标题", pad("ID", 6), pad("状态", 10), pad("更新时间", 16), pad("评论", 6));
+            for post in posts {
+                println!(
+                    "{} {} {} {} {}",
+                    pad(&post.id.to_string(), 6),
+                    pad(post.state.as_str(), 10),
+                    pad(&post.updated_at.format(TIME_FORMAT).to_string(), 16),
+                    pad(&count_of(post).to_string(), 6),
+                    post.title
+                );
+            }
+            println!("共 {} 篇文章", posts.len());
+            Ok(())
+        }
+        format => {
+            let listed: Vec<_> = posts.iter().map(|post| ListedPost { post, comments: count_of(post) }).collect();
+            print_json(&listed, format)
+        }
+    }
+}
+
+pub fn print_thread(thread: &PostThread, format: Format) -> serde_json::Result<()> {
+    match format {
+        Format::Table => {
+            println!("文章ID: {}", thread.post.id);
+            println!("标题: {}", thread.post.title);
+            println!("评论:");
+            print_comment_nodes(&thread.comments, 1);
+            Ok(())
+        }
+        format => print_json(thread, format),
+    }
+}
+
+fn print_comment_nodes(nodes: &[CommentNode], depth: usize) {
+    for node in nodes {
+        let comment = &node.comment;
+        let indent = "  ".repeat(depth);
+        println!(
+            "{}#{} {} ({}, {}): {}",
+            indent,
+            comment.id,
+            comment.author,
+            comment.status,
+            comment.created_at.format(TIME_FORMAT),
+            comment.body
+        );
+        print_comment_nodes(&node.replies, depth + 1);
+    }
+}
+
+pub fn print_hits(hits: &[SearchHit], format: Format) -> serde_json::Result<()> {
+    match format {
+        Format::Table => {
+            for hit in hits {
+                println!("{:<6} {:.3}  {}", hit.post.id, hit.rank, hit.post.title);
+                println!("       {}", hit.snippet.replace('\n', " "));
+            }
+            println!("共 {} 篇文章", hits.len());
+            Ok(())
+        }
+        format => print_json(hits, format),
+    }
+}
+
+pub fn print_tags(tags: &[Tag], format: Format) -> serde_json::Result<()> {
+    match format {
+        Format::Table => {
+            println!("标签: {}", join_names(tags));
+            Ok(())
+        }
+        format => print_json(tags, format),
+    }
+}
+
+pub fn print_tagged_posts(posts: &[PostWithTags], format: Format) -> serde_json::Result<()> {
+    match format {
+        Format::Table => {
+            println!("{} {} {} 标签", pad("ID", 6), pad("状态", 10), pad("标题", 30));
+            for PostWithTags { post, tags } in posts {
+                println!(
+                    "{} {} {} {}",
+                    pad(&post.id.to_string(), 6),
+                    pad(post.state.as_str(), 10),
+                    pad(&post.title, 30),
+                    join_names(tags)
+                );
+            }
+            println!("共 {} 篇文章", posts.len());
+            Ok(())
+        }
+        format => print_json(posts, format),
+    }
+}
+
+pub fn print_tag_cloud(cloud: &[(Tag, i64)], format: Format) -> serde_json::Result<()> {
+    match format {
+        Format::Table => {
+            for (tag, count) in cloud {
+                println!("{} {}", pad(&tag.name, 20), count);
+            }
+            Ok(())
+        }
+        format => {
+            let entries: Vec<_> = cloud
+                .iter()
+                .map(|(tag, count)| serde_json::json!({ "name": tag.name, "posts": count }))
+                .collect();
+            print_json(&entries, format)
+        }
+    }
+}
+
+pub fn print_preview(preview: &Preview<Post>) {
+    println!("SQL: {}", preview.sql);
+    println!("将影响 {} 篇帖子：", preview.affected);
+    for post in &preview.sample {
+        println!("  {:<6} {}", post.id, post.title);
+    }
+    if preview.affected > preview.sample.len() {
+        println!("  ... 以及另外 {} 篇", preview.affected - preview.sample.len());
+    }
+}
+
+/// 按显示宽度在右侧补空格，中文字符占两列；`{:<n}` 按字符数补齐，遇到中文会错位
+fn pad(cell: &str, width: usize) -> String {
+    format!("{}{}", cell, " ".repeat(width.saturating_sub(cell.width())))
+}
+
+fn join_names(tags: &[Tag]) -> String {
+    tags.iter().map(|tag| tag.name.as_str()).collect::<Vec<_>>().join(", ")
+}
+
+/// JSON 格式整体美化输出，NDJSON 格式把数组的每个元素输出为一行
+fn print_json<T: Serialize + ?Sized>(value: &T, format: Format) -> serde_json::Result<()> {
+    match (format, serde_json::to_value(value)?) {
+        (Format::Ndjson, serde_json::Value::Array(items)) => {
+            for item in items {
+                println!("{}", item);
+            }
+        }
+        (Format::Ndjson, value) => println!("{}", value),
+        (_, value) => println!("{}", serde_json::to_string_pretty(&value)?),
+    }
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use ch01_blog_demo_cli::models::Post;
+use ch01_blog_demo_cli::tui::app::{App, Effect};
+use ch01_blog_demo_cli::tui::editor::{parse_editor_text, to_editor_text};
+use ch01_blog_demo_cli::tui::store::PostStore;
+use ch01_blog_demo_cli::tui::ui;
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::DefaultTerminal;
+use std::error::Error;
+use std::io::{self, Write};
+use std::process;
+
+pub fn run<S: PostStore>(store: S, per_page: i64) -> Result<(), Box<dyn Error>> {
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, App::new(store, per_page));
+    ratatui::restore();
+    result
+}
+
+fn event_loop<S: PostStore>(terminal: &mut DefaultTerminal, mut app: App<S>) -> Result<(), Box<dyn Error>> {
+    loop {
+        terminal.draw(|frame| ui::draw(frame, &app))?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match app.handle_key(key.code) {
+            Effect::None => {}
+            Effect::Quit => return Ok(()),
+            Effect::Edit(post) => match suspend(terminal, || edit_in_editor(&post))? {
+                Ok(text) => match parse_editor_text(&post, &text) {
+                    Ok(changes) => app.apply_edit(post.id, changes),
+                    Err(e) => app.set_status(format!("编辑失败: {}", e)),
+                },
+                Err(e) => app.set_status(format!("无法启动编辑器: {}", e)),
+            },
+        }
+    }
+}
+
+/// 暂时离开界面，把终端交给外部程序使用
+fn suspend<T>(terminal: &mut DefaultTerminal, f: impl FnOnce() -> T) -> io::Result<T> {
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen)?;
+
+    let result = f();
+
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    enable_raw_mode()?;
+    terminal.clear()?;
+    Ok(result)
+}
+
+/// 把文章写入临时文件，用 `$EDITOR`（默认 vi）打开，返回保存后的内容
+fn edit_in_editor(post: &Post) -> io::Result<String> {
+    let mut file = tempfile::Builder::new().suffix(".md").tempfile()?;
+    file.write_all(to_editor_text(post).as_bytes())?;
+    file.flush()?;
+
+    let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vi".to_string());
+    // EDITOR 可能带参数，如 "code --wait"
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let status = process::Command::new(program).args(parts).arg(file.path()).status()?;
+    if !status.success() {
+        return Err(io::Error::other(format!("{} 退出状态 {}", editor, status)));
+    }
+
+    std::fs::read_to_string(file.path())
+}
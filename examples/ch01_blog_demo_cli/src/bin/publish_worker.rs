@@ -0,0 +1,51 @@
+use ch01_blog_demo_cli::{posts, try_establish_connection, AppError};
+use clap::Parser;
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+
+/// 定时发布任务：周期性地发布已到期的定时文章，可以同时运行多个实例
+#[derive(Parser)]
+#[command(name = "publish_worker")]
+struct Args {
+    /// 每个事务最多发布的文章数
+    #[arg(long, default_value_t = 50, value_parser = clap::value_parser!(i64).range(1..))]
+    batch_size: i64,
+    /// 没有到期文章时的轮询间隔（秒）
+    #[arg(long, default_value_t = 10)]
+    interval: u64,
+    /// 处理完当前到期的文章后退出
+    #[arg(long)]
+    once: bool,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("错误: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &Args) -> Result<(), AppError> {
+    let conn = &mut try_establish_connection()?;
+
+    loop {
+        let published = posts::publish_due(conn, args.batch_size)?;
+        for post in &published {
+            println!("已发布文章 {}: {}", post.id, post.title);
+        }
+
+        // 批次未满说明当前没有更多到期文章
+        if (published.len() as i64) < args.batch_size {
+            if args.once {
+                return Ok(());
+            }
+            thread::sleep(Duration::from_secs(args.interval));
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use diesel::deserialize::{FromSql, FromSqlRow, Result as DeserializeResult};
+use diesel::expression::AsExpression;
+use diesel::pg::{Pg, PgValue};
+use diesel::serialize::{IsNull, Output, ToSql};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::Write;
+
+use crate::schema::sql_types::BodyFormat as BodyFormatType;
+
+// 映射 Postgres 中的 body_format 枚举类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AsExpression, FromSqlRow, Serialize, Deserialize, clap::ValueEnum)]
+#[diesel(sql_type = BodyFormatType)]
+#[serde(rename_all = "lowercase")]
+pub enum BodyFormat {
+    Markdown,
+    /// 纯文本，渲染时只做转义与分段
+    Plain,
+}
+
+impl BodyFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BodyFormat::Markdown => "markdown",
+            BodyFormat::Plain => "plain",
+        }
+    }
+}
+
+impl fmt::Display for BodyFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl ToSql<BodyFormatType, Pg> for BodyFormat {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> diesel::serialize::Result {
+        out.write_all(self.as_str().as_bytes())?;
+        Ok(IsNull::No)
+    }
+}
+// 从数据库读取
+impl FromSql<BodyFormatType, Pg> for BodyFormat {
+    fn from_sql(bytes: PgValue<'_>) -> DeserializeResult<Self> {
+        match bytes.as_bytes() {
+            b"markdown" => Ok(BodyFormat::Markdown),
+            b"plain" => Ok(BodyFormat::Plain),
+            other => Err(format!("Unrecognized body_format: {}", String::from_utf8_lossy(other)).into()),
+        }
+    }
+}
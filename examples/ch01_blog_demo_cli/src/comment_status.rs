@@ -0,0 +1,54 @@
+use diesel::deserialize::{FromSql, FromSqlRow, Result as DeserializeResult};
+use diesel::expression::AsExpression;
+use diesel::pg::{Pg, PgValue};
+use diesel::serialize::{IsNull, Output, ToSql};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::Write;
+
+use crate::schema::sql_types::CommentStatus as CommentStatusType;
+
+// 映射 Postgres 中的 comment_status 枚举类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AsExpression, FromSqlRow, Serialize, Deserialize, clap::ValueEnum)]
+#[diesel(sql_type = CommentStatusType)]
+#[serde(rename_all = "lowercase")]
+pub enum CommentStatus {
+    /// 等待审核，只有作者与管理员可见
+    Pending,
+    Approved,
+    Spam,
+}
+
+impl CommentStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CommentStatus::Pending => "pending",
+            CommentStatus::Approved => "approved",
+            CommentStatus::Spam => "spam",
+        }
+    }
+}
+
+impl fmt::Display for CommentStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl ToSql<CommentStatusType, Pg> for CommentStatus {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> diesel::serialize::Result {
+        out.write_all(self.as_str().as_bytes())?;
+        Ok(IsNull::No)
+    }
+}
+// 从数据库读取
+impl FromSql<CommentStatusType, Pg> for CommentStatus {
+    fn from_sql(bytes: PgValue<'_>) -> DeserializeResult<Self> {
+        match bytes.as_bytes() {
+            b"pending" => Ok(CommentStatus::Pending),
+            b"approved" => Ok(CommentStatus::Approved),
+            b"spam" => Ok(CommentStatus::Spam),
+            other => Err(format!("Unrecognized comment_status: {}", String::from_utf8_lossy(other)).into()),
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use crate::comment_status::CommentStatus;
+use crate::models::{Comment, NewComment, Post};
+use crate::posts::find_post;
+use crate::schema::comments;
+use common::AppError;
+use diesel::dsl::count;
+use diesel::prelude::*;
+use diesel::sql_types::{Bool, Int4};
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Serialize)]
+pub struct CommentNode {
+    pub comment: Comment,
+    pub replies: Vec<CommentNode>,
+}
+
+#[derive(Serialize)]
+pub struct PostThread {
+    pub post: Post,
+    pub comments: Vec<CommentNode>,
+}
+
+/// 递归查询返回的一行：评论本身加上它在评论树中的深度
+#[derive(QueryableByName)]
+struct ThreadRow {
+    #[diesel(embed)]
+    comment: Comment,
+    #[diesel(sql_type = Int4)]
+    depth: i32,
+}
+
+/// 发表评论，回复的评论必须属于同一篇文章
+pub fn add_comment(conn: &mut PgConnection, new_comment: &NewComment) -> Result<Comment, AppError> {
+    if find_post(conn, new_comment.post_id)?.is_none() {
+        return Err(AppError::NotFound(format!("文章 {}", new_comment.post_id)));
+    }
+    if let Some(parent_id) = new_comment.parent_id {
+        let parent_post = comments::table
+            .find(parent_id)
+            .select(comments::post_id)
+            .first::<i32>(conn)
+            .optional()?;
+        match parent_post {
+            None => return Err(AppError::NotFound(format!("评论 {}", parent_id))),
+            Some(post_id) if post_id != new_comment.post_id => {
+                return Err(AppError::Validation(format!("评论 {} 不属于文章 {}", parent_id, new_comment.post_id)));
+            }
+            Some(_) => {}
+        }
+    }
+
+    Ok(diesel::insert_into(comments::table)
+        .values(new_comment)
+        .returning(Comment::as_returning())
+        .get_result(conn)?)
+}
+
+/// 修改评论的审核状态，评论不存在时返回 `None`
+pub fn moderate_comment(conn: &mut PgConnection, comment_id: i32, status: CommentStatus) -> QueryResult<Option<Comment>> {
+    diesel::update(comments::table.find(comment_id))
+        .set(comments::status.eq(status))
+        .returning(Comment::as_returning())
+        .get_result(conn)
+        .optional()
+}
+
+// #region thread
+/// 读取文章及其完整的评论树，文章不存在时返回 `None`
+///
+/// Diesel 的查询构建器不支持 `WITH RECURSIVE`，这里用 `sql_query` 一次查出整棵树，
+/// 而不是每一层回复查询一次。`include_unapproved` 为 `false` 时只返回已通过审核的评论，
+/// 未通过审核的评论下的回复也一并隐藏。
+pub fn load_thread(conn: &mut PgConnection, post_id: i32, include_unapproved: bool) -> QueryResult<Option<PostThread>> {
+    let Some(post) = find_post(conn, post_id)? else {
+        return Ok(None);
+    };
+
+    let rows = diesel::sql_query(
+        "WITH RECURSIVE thread AS (
+            SELECT c.*, 0 AS depth, ARRAY[c.id] AS path
+            FROM comments c
+            WHERE c.post_id = $1 AND c.parent_id IS NULL AND ($2 OR c.status = 'approved')
+            UNION ALL
+            SELECT c.*, t.depth + 1, t.path || c.id
+            FROM comments c
+            JOIN thread t ON c.parent_id = t.id
+            WHERE $2 OR c.status = 'approved'
+        )
+        SELECT id, post_id, parent_id, author, body, status, created_at, depth
+        FROM thread
+        ORDER BY path",
+    )
+    .bind::<Int4, _>(post_id)
+    .bind::<Bool, _>(include_unapproved)
+    .load::<ThreadRow>(conn)?;
+
+    Ok(Some(PostThread { post, comments: build_tree(rows) }))
+}
+// #endregion thread
+
+// #region counts
+/// 统计每篇文章已通过审核的评论数量，没有评论的文章不会出现在结果中
+pub fn approved_counts(conn: &mut PgConnection, post_ids: &[i32]) -> QueryResult<HashMap<i32, i64>> {
+    let counts = comments::table
+        .filter(comments::post_id.eq_any(post_ids))
+        .filter(comments::status.eq(CommentStatus::Approved))
+        .group_by(comments::post_id)
+        .select((comments::post_id, count(comments::id)))
+        .load::<(i32, i64)>(conn)?;
+
+    Ok(counts.into_iter().collect())
+}
+// #endregion counts
+
+/// 由按路径排序（即深度优先顺序）的行组装评论树
+///
+/// 栈中保存从根到当前评论的路径，遇到更浅的评论时把栈顶弹出并挂到它的父评论下。
+fn build_tree(rows: Vec<ThreadRow>) -> Vec<CommentNode> {
+    let mut roots = Vec::new();
+    let mut stack: Vec<CommentNode> = Vec::new();
+
+    for row in rows {
+        while stack.len() > row.depth as usize {
+            attach(&mut stack, &mut roots);
+        }
+        stack.push(CommentNode { comment: row.comment, replies: Vec::new() });
+    }
+    while !stack.is_empty() {
+        attach(&mut stack, &mut roots);
+    }
+
+    roots
+}
+
+fn attach(stack: &mut Vec<CommentNode>, roots: &mut Vec<CommentNode>) {
+    if let Some(node) = stack.pop() {
+        match stack.last_mut() {
+            Some(parent) => parent.replies.push(node),
+            None => roots.push(node),
+        }
+    }
+}
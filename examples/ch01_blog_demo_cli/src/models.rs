@@ -1,14 +1,27 @@
+use chrono::{DateTime, Utc};
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Queryable, Selectable, Serialize)]
+use crate::body_format::BodyFormat;
+use crate::comment_status::CommentStatus;
+use crate::post_state::PostState;
+
+#[derive(Queryable, QueryableByName, Selectable, Identifiable, Serialize, Debug, Clone, PartialEq)]
 #[diesel(table_name = crate::schema::posts)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct Post {
     pub id: i32,
     pub title: String,
+    /// 由标题生成的唯一 URL 标识
+    pub slug: String,
     pub body: String,
     pub published: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub state: PostState,
+    /// 定时发布的时间，仅 scheduled 状态有值
+    pub publish_at: Option<DateTime<Utc>>,
+    pub body_format: BodyFormat,
 }
 
 #[derive(Queryable, Selectable, Insertable)]
@@ -16,4 +29,69 @@ pub struct Post {
 pub struct NewPost {
     pub title: String,
     pub body: String,
-}
\ No newline at end of file
+    pub body_format: BodyFormat,
+}
+#[derive(Queryable, Selectable, Identifiable, Serialize, Debug, PartialEq)]
+#[diesel(table_name = crate::schema::tags)]
+pub struct Tag {
+    pub id: i32,
+    pub name: String,
+}
+
+#[derive(Queryable, Selectable, Identifiable, Associations, Insertable, Debug)]
+#[diesel(belongs_to(Post))]
+#[diesel(belongs_to(Tag))]
+#[diesel(table_name = crate::schema::post_tags)]
+#[diesel(primary_key(post_id, tag_id))]
+pub struct PostTag {
+    pub post_id: i32,
+    pub tag_id: i32,
+}
+
+/// 编辑文章时的变更，`None` 的字段保持不变
+#[derive(AsChangeset, Debug, Default, PartialEq)]
+#[diesel(table_name = crate::schema::posts)]
+pub struct UpdatePost {
+    pub title: Option<String>,
+    pub body: Option<String>,
+}
+
+/// 导入导出时使用的完整文章记录，字段与 `Post` 序列化后的 JSON 一致
+#[derive(Insertable, AsChangeset, Deserialize, Debug)]
+#[diesel(table_name = crate::schema::posts)]
+pub struct PostRecord {
+    pub id: i32,
+    pub title: String,
+    pub slug: String,
+    pub body: String,
+    pub published: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub state: PostState,
+    pub publish_at: Option<DateTime<Utc>>,
+    pub body_format: BodyFormat,
+}
+
+#[derive(Queryable, QueryableByName, Selectable, Identifiable, Associations, Serialize, Debug)]
+#[diesel(belongs_to(Post))]
+#[diesel(table_name = crate::schema::comments)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Comment {
+    pub id: i32,
+    pub post_id: i32,
+    /// 回复的评论，顶层评论为 `None`
+    pub parent_id: Option<i32>,
+    pub author: String,
+    pub body: String,
+    pub status: CommentStatus,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::comments)]
+pub struct NewComment {
+    pub post_id: i32,
+    pub parent_id: Option<i32>,
+    pub author: String,
+    pub body: String,
+}
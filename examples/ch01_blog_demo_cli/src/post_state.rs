@@ -0,0 +1,57 @@
+use diesel::deserialize::{FromSql, FromSqlRow, Result as DeserializeResult};
+use diesel::expression::AsExpression;
+use diesel::pg::{Pg, PgValue};
+use diesel::serialize::{IsNull, Output, ToSql};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::Write;
+
+use crate::schema::sql_types::PostState as PostStateType;
+
+// 映射 Postgres 中的 post_state 枚举类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AsExpression, FromSqlRow, Serialize, Deserialize)]
+#[diesel(sql_type = PostStateType)]
+#[serde(rename_all = "lowercase")]
+pub enum PostState {
+    Draft,
+    /// 已设置 publish_at，等待发布任务发布
+    Scheduled,
+    Published,
+    Archived,
+}
+
+impl PostState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PostState::Draft => "draft",
+            PostState::Scheduled => "scheduled",
+            PostState::Published => "published",
+            PostState::Archived => "archived",
+        }
+    }
+}
+
+impl fmt::Display for PostState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl ToSql<PostStateType, Pg> for PostState {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> diesel::serialize::Result {
+        out.write_all(self.as_str().as_bytes())?;
+        Ok(IsNull::No)
+    }
+}
+// 从数据库读取
+impl FromSql<PostStateType, Pg> for PostState {
+    fn from_sql(bytes: PgValue<'_>) -> DeserializeResult<Self> {
+        match bytes.as_bytes() {
+            b"draft" => Ok(PostState::Draft),
+            b"scheduled" => Ok(PostState::Scheduled),
+            b"published" => Ok(PostState::Published),
+            b"archived" => Ok(PostState::Archived),
+            other => Err(format!("Unrecognized post_state: {}", String::from_utf8_lossy(other)).into()),
+        }
+    }
+}
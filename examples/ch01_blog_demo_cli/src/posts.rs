@@ -0,0 +1,305 @@
+use crate::models::{NewPost, Post, UpdatePost};
+use crate::post_state::PostState;
+use crate::schema::{post_slug_redirects, posts};
+use crate::slug::with_unique_slug;
+use chrono::{DateTime, Utc};
+use common::dry_run::{dry_run, Preview};
+use common::query_log::LogRowsDsl;
+use common::AppError;
+use diesel::dsl;
+use diesel::prelude::*;
+
+/// 列表查询要展示的文章范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    Published,
+    Drafts,
+    All,
+}
+
+/// 按 id 或 slug 查找文章的结果
+pub enum Lookup {
+    Found(Post),
+    /// 通过旧 slug 找到，调用方应重定向到文章当前的 slug
+    Redirect(Post),
+}
+
+/// 列表排序字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SortKey {
+    Id,
+    Title,
+    CreatedAt,
+    UpdatedAt,
+}
+
+pub struct ListOptions {
+    pub visibility: Visibility,
+    pub sort: SortKey,
+    pub descending: bool,
+    /// 只保留在该时间之后创建的文章
+    pub created_after: Option<DateTime<Utc>>,
+    /// 只保留在该时间之后更新过的文章
+    pub updated_after: Option<DateTime<Utc>>,
+    pub limit: i64,
+}
+
+// #region create
+/// 创建文章，并根据标题生成唯一的 slug
+pub fn create_post(conn: &mut PgConnection, new_post: &NewPost, publish: bool) -> QueryResult<Post> {
+    with_unique_slug(conn, &new_post.title, None, |conn, slug| {
+        diesel::insert_into(posts::table)
+            .values((
+                new_post,
+                posts::slug.eq(slug),
+                posts::published.eq(publish),
+                posts::state.eq(published_state(publish)),
+            ))
+            .returning(Post::as_returning())
+            .get_result(conn)
+    })
+}
+// #endregion create
+
+// #region get
+pub fn find_post(conn: &mut PgConnection, post_id: i32) -> QueryResult<Option<Post>> {
+    posts::table
+        .find(post_id)
+        .select(Post::as_select())
+        .first(conn)
+        .optional()
+}
+// #endregion get
+
+// #region lookup
+/// 纯数字的 `key` 按 id 查找，否则按当前 slug 查找，再查找旧 slug 的重定向
+pub fn lookup_post(conn: &mut PgConnection, key: &str) -> QueryResult<Option<Lookup>> {
+    if let Ok(post_id) = key.parse::<i32>() {
+        return Ok(find_post(conn, post_id)?.map(Lookup::Found));
+    }
+
+    let current = posts::table
+        .filter(posts::slug.eq(key))
+        .select(Post::as_select())
+        .first(conn)
+        .optional()?;
+    if let Some(post) = current {
+        return Ok(Some(Lookup::Found(post)));
+    }
+
+    post_slug_redirects::table
+        .inner_join(posts::table)
+        .filter(post_slug_redirects::old_slug.eq(key))
+        .select(Post::as_select())
+        .first(conn)
+        .map(Lookup::Redirect)
+        .optional()
+}
+// #endregion lookup
+
+/// 按 id 顺序读取第 `page` 页（从 0 开始），同时返回文章总数
+pub fn page_posts(conn: &mut PgConnection, page: i64, per_page: i64) -> QueryResult<(Vec<Post>, i64)> {
+    let total = posts::table.count().get_result(conn)?;
+    let items = posts::table
+        .order(posts::id.asc())
+        .limit(per_page)
+        .offset(page * per_page)
+        .select(Post::as_select())
+        .load_logged(conn)?;
+    Ok((items, total))
+}
+
+// #region update
+/// 按变更集修改文章，修改标题时同时更新 slug，文章不存在时返回 `None`
+pub fn update_post(conn: &mut PgConnection, post_id: i32, changes: &UpdatePost) -> QueryResult<Option<Post>> {
+    conn.transaction(|conn| {
+        if let Some(title) = &changes.title
+            && rename_post(conn, post_id, title)?.is_none()
+        {
+            return Ok(None);
+        }
+        if changes.body.is_none() {
+            return find_post(conn, post_id);
+        }
+
+        diesel::update(posts::table.find(post_id))
+            .set(changes)
+            .returning(Post::as_returning())
+            .get_result(conn)
+            .optional()
+    })
+}
+// #endregion update
+
+// #region rename
+/// 修改标题并重新生成 slug，旧 slug 记录为重定向，文章不存在时返回 `None`
+pub fn rename_post(conn: &mut PgConnection, post_id: i32, title: &str) -> QueryResult<Option<Post>> {
+    conn.transaction(|conn| {
+        let Some(old_slug) = posts::table
+            .find(post_id)
+            .select(posts::slug)
+            .for_update()
+            .first::<String>(conn)
+            .optional()?
+        else {
+            return Ok(None);
+        };
+
+        with_unique_slug(conn, title, Some(post_id), |conn, new_slug| {
+            if new_slug != old_slug {
+                diesel::insert_into(post_slug_redirects::table)
+                    .values((post_slug_redirects::old_slug.eq(&old_slug), post_slug_redirects::post_id.eq(post_id)))
+                    .execute(conn)?;
+                // 改回曾经用过的标题时，对应的重定向不再需要
+                diesel::delete(post_slug_redirects::table.find(new_slug)).execute(conn)?;
+            }
+
+            diesel::update(posts::table.find(post_id))
+                .set((posts::title.eq(title), posts::slug.eq(new_slug)))
+                .returning(Post::as_returning())
+                .get_result(conn)
+        })
+        .map(Some)
+    })
+}
+// #endregion rename
+
+// #region list
+pub fn list_posts(conn: &mut PgConnection, options: &ListOptions) -> QueryResult<Vec<Post>> {
+    let mut query = posts::table.into_boxed();
+
+    match options.visibility {
+        Visibility::Published => query = query.filter(posts::published.eq(true)),
+        Visibility::Drafts => query = query.filter(posts::published.eq(false)),
+        Visibility::All => {}
+    }
+
+    if let Some(created_after) = options.created_after {
+        query = query.filter(posts::created_at.ge(created_after));
+    }
+    if let Some(updated_after) = options.updated_after {
+        query = query.filter(posts::updated_at.ge(updated_after));
+    }
+
+    query = match (options.sort, options.descending) {
+        (SortKey::Id, false) => query.order(posts::id.asc()),
+        (SortKey::Id, true) => query.order(posts::id.desc()),
+        (SortKey::Title, false) => query.order((posts::title.asc(), posts::id.asc())),
+        (SortKey::Title, true) => query.order((posts::title.desc(), posts::id.desc())),
+        (SortKey::CreatedAt, false) => query.order((posts::created_at.asc(), posts::id.asc())),
+        (SortKey::CreatedAt, true) => query.order((posts::created_at.desc(), posts::id.desc())),
+        (SortKey::UpdatedAt, false) => query.order((posts::updated_at.asc(), posts::id.asc())),
+        (SortKey::UpdatedAt, true) => query.order((posts::updated_at.desc(), posts::id.desc())),
+    };
+
+    query
+        .limit(options.limit)
+        .select(Post::as_select())
+        .load_logged(conn)
+}
+// #endregion list
+
+// #region publish
+/// 修改文章的发布状态，文章不存在时返回 `None`
+pub fn set_published(conn: &mut PgConnection, post_id: i32, published: bool) -> QueryResult<Option<Post>> {
+    diesel::update(posts::table.find(post_id))
+        .set((
+            posts::published.eq(published),
+            posts::state.eq(published_state(published)),
+            posts::publish_at.eq(None::<DateTime<Utc>>),
+        ))
+        .returning(Post::as_returning())
+        .get_result(conn)
+        .optional()
+}
+// #endregion publish
+
+/// 将文章设置为在 `publish_at` 时由发布任务自动发布
+pub fn schedule_post(conn: &mut PgConnection, post_id: i32, publish_at: DateTime<Utc>) -> QueryResult<Option<Post>> {
+    diesel::update(posts::table.find(post_id))
+        .set((
+            posts::published.eq(false),
+            posts::state.eq(PostState::Scheduled),
+            posts::publish_at.eq(Some(publish_at)),
+        ))
+        .returning(Post::as_returning())
+        .get_result(conn)
+        .optional()
+}
+
+pub fn archive_post(conn: &mut PgConnection, post_id: i32) -> QueryResult<Option<Post>> {
+    diesel::update(posts::table.find(post_id))
+        .set((
+            posts::published.eq(false),
+            posts::state.eq(PostState::Archived),
+            posts::publish_at.eq(None::<DateTime<Utc>>),
+        ))
+        .returning(Post::as_returning())
+        .get_result(conn)
+        .optional()
+}
+
+// #region publish_due
+/// 发布最多 `batch_size` 篇已到期的定时文章
+///
+/// 使用 `FOR UPDATE SKIP LOCKED` 锁定待发布的行，其他发布任务会跳过已被锁定的文章，
+/// 因此可以同时运行多个发布任务而不会重复发布同一篇文章。
+pub fn publish_due(conn: &mut PgConnection, batch_size: i64) -> QueryResult<Vec<Post>> {
+    conn.transaction(|conn| {
+        let due_ids = posts::table
+            .filter(posts::state.eq(PostState::Scheduled))
+            .filter(posts::publish_at.le(Utc::now()))
+            .order(posts::publish_at.asc())
+            .limit(batch_size)
+            .select(posts::id)
+            .for_update()
+            .skip_locked()
+            .load::<i32>(conn)?;
+
+        if due_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        diesel::update(posts::table.filter(posts::id.eq_any(&due_ids)))
+            .set((
+                posts::published.eq(true),
+                posts::state.eq(PostState::Published),
+                posts::publish_at.eq(None::<DateTime<Utc>>),
+            ))
+            .returning(Post::as_returning())
+            .load_logged(conn)
+    })
+}
+// #endregion publish_due
+
+// #region delete
+/// 标题包含 `target` 的文章
+#[dsl::auto_type]
+pub fn title_like(target: &str) -> _ {
+    let pattern: String = like_pattern(target);
+    posts::table.filter(posts::title.like(pattern))
+}
+
+/// 试运行删除并回滚，返回将被删除的文章数量与前 `sample_size` 篇文章
+pub fn preview_delete_title_like(conn: &mut PgConnection, target: &str, sample_size: usize) -> Result<Preview<Post>, AppError> {
+    let statement = diesel::delete(title_like(target)).returning(Post::as_returning());
+    dry_run(conn, statement, sample_size)
+}
+
+/// 删除单篇文章，返回文章是否存在
+pub fn delete_post(conn: &mut PgConnection, post_id: i32) -> QueryResult<bool> {
+    diesel::delete(posts::table.find(post_id)).execute_logged(conn).map(|deleted| deleted > 0)
+}
+
+pub fn delete_title_like(conn: &mut PgConnection, target: &str) -> QueryResult<usize> {
+    diesel::delete(title_like(target)).execute_logged(conn)
+}
+// #endregion delete
+
+fn published_state(published: bool) -> PostState {
+    if published { PostState::Published } else { PostState::Draft }
+}
+
+fn like_pattern(target: &str) -> String {
+    format!("%{}%", target)
+}
@@ -0,0 +1,125 @@
+//! 把文章正文渲染为 HTML，并导出不依赖数据库的静态站点
+use crate::body_format::BodyFormat;
+use crate::models::Post;
+use crate::schema::posts;
+use common::AppError;
+use diesel::prelude::*;
+use pulldown_cmark::{html, Options, Parser};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+// #region render
+/// 把正文渲染为 HTML 片段
+///
+/// markdown 中允许内嵌原始 HTML，渲染结果必须经过 ammonia 清洗，去掉 `<script>`、事件属性等危险内容。
+pub fn render_body(body: &str, format: BodyFormat) -> String {
+    match format {
+        BodyFormat::Markdown => {
+            let parser = Parser::new_ext(body, Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH);
+            let mut unsafe_html = String::new();
+            html::push_html(&mut unsafe_html, parser);
+            ammonia::clean(&unsafe_html)
+        }
+        // 纯文本按空行分段，段内换行保留为 <br>
+        BodyFormat::Plain => body
+            .split("\n\n")
+            .map(str::trim)
+            .filter(|paragraph| !paragraph.is_empty())
+            .map(|paragraph| format!("<p>{}</p>\n", escape(paragraph).replace('\n', "<br>\n")))
+            .collect(),
+    }
+}
+// #endregion render
+
+/// 渲染单篇文章的标题与正文
+pub fn render_post(post: &Post) -> String {
+    format!(
+        "<article>\n<h1>{}</h1>\n<p><time>{}</time></p>\n{}</article>\n",
+        escape(&post.title),
+        post.created_at.format(DATE_FORMAT),
+        render_body(&post.body, post.body_format)
+    )
+}
+
+// #region export
+/// 把所有已发布的文章导出为静态站点，返回导出的文章数量
+///
+/// 目录结构为 `index.html` 加上每篇文章一个的 `posts/<slug>.html`。之前导出、但文章已被取消发布、
+/// 删除或修改了 slug 的页面会从 `posts/` 中删除。
+pub fn export_site(conn: &mut PgConnection, out_dir: &Path) -> Result<usize, AppError> {
+    let published = posts::table
+        .filter(posts::published.eq(true))
+        .order((posts::created_at.desc(), posts::id.desc()))
+        .select(Post::as_select())
+        .load(conn)?;
+
+    let posts_dir = out_dir.join("posts");
+    fs::create_dir_all(&posts_dir)?;
+
+    let mut written = HashSet::new();
+    for post in &published {
+        let content = format!("<p><a href=\"../index.html\">返回首页</a></p>\n{}", render_post(post));
+        let file_name = post_file_name(post);
+        fs::write(posts_dir.join(&file_name), page(&post.title, &content))?;
+        written.insert(file_name);
+    }
+    remove_stale_pages(&posts_dir, &written)?;
+
+    let mut index = String::from("<h1>文章列表</h1>\n<ul>\n");
+    for post in &published {
+        index.push_str(&format!(
+            "<li><time>{}</time> <a href=\"posts/{}\">{}</a></li>\n",
+            post.created_at.format(DATE_FORMAT),
+            post_file_name(post),
+            escape(&post.title)
+        ));
+    }
+    index.push_str("</ul>\n");
+    fs::write(out_dir.join("index.html"), page("博客", &index))?;
+
+    Ok(published.len())
+}
+// #endregion export
+
+/// 删除 `posts_dir` 中不属于本次导出的 `.html` 文件，其他文件保持不变
+fn remove_stale_pages(posts_dir: &Path, written: &HashSet<String>) -> Result<(), AppError> {
+    for entry in fs::read_dir(posts_dir)? {
+        let path = entry?.path();
+        let stale = path.extension().is_some_and(|ext| ext == "html")
+            && path.file_name().and_then(|name| name.to_str()).is_none_or(|name| !written.contains(name));
+        if stale && path.is_file() {
+            fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+fn post_file_name(post: &Post) -> String {
+    format!("{}.html", post.slug)
+}
+
+fn page(title: &str, content: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"zh-CN\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape(title),
+        content
+    )
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
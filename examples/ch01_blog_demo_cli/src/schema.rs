@@ -1,10 +1,90 @@
 // @generated automatically by Diesel CLI.
 
+pub mod sql_types {
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "body_format"))]
+    pub struct BodyFormat;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "comment_status"))]
+    pub struct CommentStatus;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "post_state"))]
+    pub struct PostState;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "tsvector", schema = "pg_catalog"))]
+    pub struct Tsvector;
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::CommentStatus;
+
+    comments (id) {
+        id -> Int4,
+        post_id -> Int4,
+        parent_id -> Nullable<Int4>,
+        author -> Varchar,
+        body -> Text,
+        status -> CommentStatus,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    post_slug_redirects (old_slug) {
+        old_slug -> Varchar,
+        post_id -> Int4,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
+    post_tags (post_id, tag_id) {
+        post_id -> Int4,
+        tag_id -> Int4,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::PostState;
+    use super::sql_types::Tsvector;
+    use super::sql_types::BodyFormat;
+
     posts (id) {
         id -> Int4,
         title -> Varchar,
         body -> Text,
         published -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        state -> PostState,
+        publish_at -> Nullable<Timestamptz>,
+        search_vector -> Tsvector,
+        body_format -> BodyFormat,
+        slug -> Varchar,
     }
 }
+
+diesel::table! {
+    tags (id) {
+        id -> Int4,
+        name -> Varchar,
+    }
+}
+
+diesel::joinable!(comments -> posts (post_id));
+diesel::joinable!(post_slug_redirects -> posts (post_id));
+diesel::joinable!(post_tags -> posts (post_id));
+diesel::joinable!(post_tags -> tags (tag_id));
+
+diesel::allow_tables_to_appear_in_same_query!(
+    comments,
+    post_slug_redirects,
+    post_tags,
+    posts,
+    tags,
+);
@@ -0,0 +1,118 @@
+//! Postgres 全文搜索
+//!
+//! Diesel 没有内建 `tsvector`、`tsquery` 相关的类型与函数，这里通过 `define_sql_function!`
+//! 和 `infix_operator!` 自行声明，之后就可以像内建函数一样在查询构建器中使用。
+use crate::models::Post;
+use crate::schema::posts;
+use crate::schema::sql_types::Tsvector;
+use diesel::expression::{AppearsOnTable, AsExpression, Expression, SelectableExpression, ValidGrouping};
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_builder::{AstPass, QueryFragment, QueryId};
+use diesel::sql_types::{SqlType, Text};
+
+/// 片段中命中词的高亮标记与长度
+const HEADLINE_OPTIONS: &str = "StartSel=[, StopSel=], MinWords=10, MaxWords=30, MaxFragments=2";
+
+// #region sql_types
+#[derive(Debug, Clone, Copy, SqlType, QueryId)]
+#[diesel(postgres_type(name = "tsquery", schema = "pg_catalog"))]
+pub struct TsQuery;
+
+#[derive(Debug, Clone, Copy, SqlType, QueryId)]
+#[diesel(postgres_type(name = "regconfig", schema = "pg_catalog"))]
+pub struct RegConfig;
+// #endregion sql_types
+
+// #region functions
+define_sql_function! {
+    /// 按指定配置把文本切分为 tsvector
+    fn to_tsvector(config: RegConfig, document: Text) -> Tsvector;
+}
+
+define_sql_function! {
+    /// 把用户输入的纯文本转换为 tsquery，各个词之间以 AND 连接
+    fn plainto_tsquery(config: RegConfig, query: Text) -> TsQuery;
+}
+
+define_sql_function! {
+    fn ts_rank(vector: Tsvector, query: TsQuery) -> Float4;
+}
+
+define_sql_function! {
+    /// 返回文档中命中查询词附近的片段
+    fn ts_headline(config: RegConfig, document: Text, query: TsQuery, options: Text) -> Text;
+}
+
+diesel::infix_operator!(Matches, " @@ ", backend: Pg);
+
+pub trait TsVectorExpressionMethods: Expression<SqlType = Tsvector> + Sized {
+    /// `tsvector @@ tsquery`
+    fn matches<T>(self, query: T) -> Matches<Self, T::Expression>
+    where
+        T: AsExpression<TsQuery>,
+    {
+        Matches::new(self, query.as_expression())
+    }
+}
+
+impl<T: Expression<SqlType = Tsvector>> TsVectorExpressionMethods for T {}
+// #endregion functions
+
+/// 与 `search_vector` 生成列一致的文本搜索配置，即 `'simple'::regconfig`
+///
+/// regconfig 在二进制协议中以 OID 传输，无法直接绑定字符串参数，因此作为 SQL 字面量写入查询。
+#[derive(Debug, Clone, Copy, QueryId, ValidGrouping)]
+pub struct SearchConfig;
+
+impl Expression for SearchConfig {
+    type SqlType = RegConfig;
+}
+
+impl<QS> AppearsOnTable<QS> for SearchConfig {}
+
+impl<QS> SelectableExpression<QS> for SearchConfig {}
+
+impl QueryFragment<Pg> for SearchConfig {
+    fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, Pg>) -> QueryResult<()> {
+        out.push_sql("'simple'::regconfig");
+        Ok(())
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct SearchHit {
+    pub post: Post,
+    pub rank: f32,
+    /// 正文中命中查询词的片段，命中词以 `[` `]` 标出
+    pub snippet: String,
+}
+
+// #region search
+/// 按相关度从高到低搜索标题与正文，`include_unpublished` 为 `false` 时只搜索已发布的文章
+pub fn search_posts(conn: &mut PgConnection, text: &str, include_unpublished: bool, limit: i64) -> QueryResult<Vec<SearchHit>> {
+    let query = plainto_tsquery(SearchConfig, text);
+
+    let mut statement = posts::table
+        .filter(posts::search_vector.matches(query))
+        .into_boxed();
+    if !include_unpublished {
+        statement = statement.filter(posts::published.eq(true));
+    }
+
+    let rows = statement
+        .order((ts_rank(posts::search_vector, query).desc(), posts::id.asc()))
+        .limit(limit)
+        .select((
+            Post::as_select(),
+            ts_rank(posts::search_vector, query),
+            ts_headline(SearchConfig, posts::body, query, HEADLINE_OPTIONS),
+        ))
+        .load::<(Post, f32, String)>(conn)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(post, rank, snippet)| SearchHit { post, rank, snippet })
+        .collect())
+}
+// #endregion search
@@ -0,0 +1,137 @@
+//! 根据标题生成唯一的 URL slug
+use crate::schema::{post_slug_redirects, posts};
+use diesel::prelude::*;
+use diesel::result::{DatabaseErrorKind, Error};
+use std::collections::HashSet;
+
+/// 标题无法生成 slug 时使用的前缀
+const FALLBACK_SLUG: &str = "post";
+const MAX_SLUG_LEN: usize = 80;
+/// `posts.slug` 上的唯一约束
+const SLUG_CONSTRAINT: &str = "posts_slug_key";
+/// 并发写入抢占了同一个 slug 时最多尝试的次数
+const MAX_SLUG_ATTEMPTS: usize = 5;
+
+/// 把标题转换为 slug
+///
+/// 中文等非 ASCII 字符会被音译为拼音，如 "Rust 快速开始" 转换为 "rust-kuai-su-kai-shi"；
+/// 无法音译的标题回退为 `post`，纯数字的结果加上 `post-` 前缀，避免与文章 id 混淆。
+pub fn slugify_title(title: &str) -> String {
+    let mut slug = slug::slugify(title);
+    if slug.len() > MAX_SLUG_LEN {
+        slug.truncate(MAX_SLUG_LEN);
+        slug.truncate(slug.trim_end_matches('-').len());
+    }
+
+    if slug.is_empty() {
+        FALLBACK_SLUG.to_string()
+    } else if slug.bytes().all(|b| b.is_ascii_digit()) {
+        format!("{}-{}", FALLBACK_SLUG, slug)
+    } else {
+        slug
+    }
+}
+
+/// slug 只能由小写字母、数字和单个连字符组成，与数据库中的 CHECK 约束一致
+///
+/// 纯数字的 slug 会被 `lookup_post` 当作文章 id，无法通过 slug 访问，因此同样视为不合法。
+pub fn is_valid_slug(slug: &str) -> bool {
+    let well_formed = slug
+        .split('-')
+        .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit()));
+    well_formed && !slug.bytes().all(|b| b.is_ascii_digit())
+}
+
+// #region unique
+/// 为标题生成尚未被占用的 slug，冲突时依次追加 `-2`、`-3` 等后缀
+///
+/// 其他文章的当前 slug 与重定向中的旧 slug 都视为已占用；`post_id` 为正在修改标题的文章，
+/// 它自己的旧 slug 可以重新使用。
+pub fn unique_slug(conn: &mut PgConnection, title: &str, post_id: Option<i32>) -> QueryResult<String> {
+    let base = slugify_title(title);
+    let suffixed = format!("{}-%", base);
+    // id 从 1 开始，新文章用 0 表示不排除任何文章
+    let owner = post_id.unwrap_or(0);
+
+    let mut taken: HashSet<String> = posts::table
+        .filter(posts::slug.eq(&base).or(posts::slug.like(&suffixed)))
+        .filter(posts::id.ne(owner))
+        .select(posts::slug)
+        .load::<String>(conn)?
+        .into_iter()
+        .collect();
+    taken.extend(
+        post_slug_redirects::table
+            .filter(post_slug_redirects::old_slug.eq(&base).or(post_slug_redirects::old_slug.like(&suffixed)))
+            .filter(post_slug_redirects::post_id.ne(owner))
+            .select(post_slug_redirects::old_slug)
+            .load::<String>(conn)?,
+    );
+
+    if !taken.contains(&base) {
+        return Ok(base);
+    }
+    Ok((2..)
+        .map(|n| format!("{}-{}", base, n))
+        .find(|candidate| !taken.contains(candidate))
+        .expect("后缀是无限序列，总能找到未占用的 slug"))
+}
+
+/// 用 `unique_slug` 生成的 slug 执行 `write`
+///
+/// 生成与写入之间，并发的请求可能抢先占用同一个 slug，此时写入违反 `posts_slug_key`，
+/// 重新生成 slug 后重试。每次写入都在保存点中执行，失败只回滚这一次尝试，外层事务仍然可用。
+pub fn with_unique_slug<T>(
+    conn: &mut PgConnection,
+    title: &str,
+    post_id: Option<i32>,
+    mut write: impl FnMut(&mut PgConnection, &str) -> QueryResult<T>,
+) -> QueryResult<T> {
+    let mut attempt = 1;
+    loop {
+        let slug = unique_slug(conn, title, post_id)?;
+        match conn.transaction(|conn| write(conn, &slug)) {
+            Err(Error::DatabaseError(DatabaseErrorKind::UniqueViolation, info))
+                if attempt < MAX_SLUG_ATTEMPTS && info.constraint_name() == Some(SLUG_CONSTRAINT) =>
+            {
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+// #endregion unique
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_slugs() {
+        assert!(is_valid_slug("rust-kuai-su-kai-shi"));
+        assert!(is_valid_slug("post-123"));
+        assert!(is_valid_slug("2024-recap"));
+    }
+
+    #[test]
+    fn malformed_slugs_are_invalid() {
+        assert!(!is_valid_slug(""));
+        assert!(!is_valid_slug("Rust"));
+        assert!(!is_valid_slug("rust--diesel"));
+        assert!(!is_valid_slug("-rust"));
+        assert!(!is_valid_slug("rust_diesel"));
+    }
+
+    #[test]
+    fn numeric_slugs_are_invalid() {
+        assert!(!is_valid_slug("123"));
+        assert!(!is_valid_slug("0"));
+    }
+
+    #[test]
+    fn slugified_titles_are_valid() {
+        for title in ["123", "Rust 快速开始", "!!!", "2024"] {
+            assert!(is_valid_slug(&slugify_title(title)), "{}", title);
+        }
+    }
+}
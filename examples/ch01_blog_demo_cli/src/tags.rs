@@ -0,0 +1,175 @@
+use crate::models::{Post, PostTag, Tag};
+use crate::schema::{post_tags, posts, tags};
+use common::AppError;
+use diesel::dsl::count;
+use diesel::prelude::*;
+use serde::Serialize;
+
+/// 按多个标签筛选文章时的匹配方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagMatch {
+    /// 同时带有全部标签
+    All,
+    /// 带有任意一个标签
+    Any,
+}
+
+#[derive(Serialize)]
+pub struct PostWithTags {
+    pub post: Post,
+    pub tags: Vec<Tag>,
+}
+
+// #region tag
+/// 为文章添加标签，不存在的标签会自动创建，已添加的标签会被忽略，返回文章当前的全部标签
+pub fn tag_post(conn: &mut PgConnection, post_id: i32, names: &[String]) -> Result<Vec<Tag>, AppError> {
+    let names = normalize_names(names)?;
+
+    conn.transaction(|conn| {
+        ensure_post_exists(conn, post_id)?;
+
+        let new_tags: Vec<_> = names.iter().map(|name| tags::name.eq(name)).collect();
+        diesel::insert_into(tags::table)
+            .values(&new_tags)
+            .on_conflict(tags::name)
+            .do_nothing()
+            .execute(conn)?;
+
+        let links: Vec<PostTag> = tags::table
+            .filter(tags::name.eq_any(&names))
+            .select(tags::id)
+            .load::<i32>(conn)?
+            .into_iter()
+            .map(|tag_id| PostTag { post_id, tag_id })
+            .collect();
+        diesel::insert_into(post_tags::table)
+            .values(&links)
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+
+        Ok(tags_for_post(conn, post_id)?)
+    })
+}
+// #endregion tag
+
+/// 移除文章的标签，文章没有的标签会被忽略，返回文章剩余的标签
+pub fn untag_post(conn: &mut PgConnection, post_id: i32, names: &[String]) -> Result<Vec<Tag>, AppError> {
+    let names = normalize_names(names)?;
+
+    conn.transaction(|conn| {
+        ensure_post_exists(conn, post_id)?;
+
+        let tag_ids = tags::table.filter(tags::name.eq_any(&names)).select(tags::id);
+        diesel::delete(
+            post_tags::table
+                .filter(post_tags::post_id.eq(post_id))
+                .filter(post_tags::tag_id.eq_any(tag_ids)),
+        )
+        .execute(conn)?;
+
+        Ok(tags_for_post(conn, post_id)?)
+    })
+}
+
+pub fn tags_for_post(conn: &mut PgConnection, post_id: i32) -> QueryResult<Vec<Tag>> {
+    post_tags::table
+        .inner_join(tags::table)
+        .filter(post_tags::post_id.eq(post_id))
+        .order(tags::name.asc())
+        .select(Tag::as_select())
+        .load(conn)
+}
+
+// #region tagged
+/// 查询带有指定标签的文章，`include_unpublished` 为 `false` 时只返回已发布的文章
+pub fn posts_with_tags(
+    conn: &mut PgConnection,
+    names: &[String],
+    tag_match: TagMatch,
+    include_unpublished: bool,
+) -> Result<Vec<PostWithTags>, AppError> {
+    let names = normalize_names(names)?;
+
+    let matching = post_tags::table
+        .inner_join(tags::table)
+        .filter(tags::name.eq_any(names.clone()));
+
+    let mut query = posts::table.into_boxed();
+    query = match tag_match {
+        TagMatch::Any => query.filter(posts::id.eq_any(matching.select(post_tags::post_id))),
+        // 按文章分组后，命中的标签数等于查询的标签数即说明全部命中
+        TagMatch::All => query.filter(
+            posts::id.eq_any(
+                matching
+                    .group_by(post_tags::post_id)
+                    .having(count(post_tags::tag_id).eq(names.len() as i64))
+                    .select(post_tags::post_id),
+            ),
+        ),
+    };
+    if !include_unpublished {
+        query = query.filter(posts::published.eq(true));
+    }
+
+    let posts = query.order(posts::id.asc()).select(Post::as_select()).load(conn)?;
+
+    // 一次查询取回所有文章的标签，再按文章分组，避免逐篇查询
+    let tags = PostTag::belonging_to(&posts)
+        .inner_join(tags::table)
+        .order(tags::name.asc())
+        .select((PostTag::as_select(), Tag::as_select()))
+        .load::<(PostTag, Tag)>(conn)?
+        .grouped_by(&posts);
+
+    Ok(posts
+        .into_iter()
+        .zip(tags)
+        .map(|(post, tags)| PostWithTags {
+            post,
+            tags: tags.into_iter().map(|(_, tag)| tag).collect(),
+        })
+        .collect())
+}
+// #endregion tagged
+
+// #region cloud
+/// 标签云：每个标签下已发布文章的数量，没有已发布文章的标签不会出现
+pub fn tag_cloud(conn: &mut PgConnection) -> QueryResult<Vec<(Tag, i64)>> {
+    tags::table
+        .inner_join(post_tags::table.inner_join(posts::table))
+        .filter(posts::published.eq(true))
+        .group_by(tags::id)
+        .order((count(post_tags::post_id).desc(), tags::name.asc()))
+        .select((Tag::as_select(), count(post_tags::post_id)))
+        .load(conn)
+}
+// #endregion cloud
+
+/// 去除首尾空白并转为小写，去掉重复的标签名
+fn normalize_names(names: &[String]) -> Result<Vec<String>, AppError> {
+    let mut normalized = Vec::with_capacity(names.len());
+    for name in names {
+        let name = name.trim().to_lowercase();
+        if name.is_empty() {
+            return Err(AppError::Validation("标签名不能为空".into()));
+        }
+        if !normalized.contains(&name) {
+            normalized.push(name);
+        }
+    }
+
+    if normalized.is_empty() {
+        return Err(AppError::Validation("至少需要指定一个标签".into()));
+    }
+    Ok(normalized)
+}
+
+fn ensure_post_exists(conn: &mut PgConnection, post_id: i32) -> Result<(), AppError> {
+    posts::table
+        .find(post_id)
+        .select(posts::id)
+        .first::<i32>(conn)
+        .optional()?
+        .map(|_| ())
+        .ok_or_else(|| AppError::NotFound(format!("文章 {}", post_id)))
+}
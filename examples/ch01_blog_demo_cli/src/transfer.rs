@@ -0,0 +1,306 @@
+//! 以 JSON / NDJSON 格式在不同环境之间导入导出文章
+use crate::models::{Post, PostRecord};
+use crate::post_state::PostState;
+use crate::schema::posts;
+use crate::slug::is_valid_slug;
+use common::AppError;
+use common::cursor::for_each_fetched;
+use diesel::pg::PgRowByRowLoadingMode;
+use diesel::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+
+/// 单条 INSERT 语句包含的最大记录数，避免超出绑定参数数量限制
+const IMPORT_CHUNK_SIZE: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpFormat {
+    /// 一个 JSON 数组
+    Json,
+    /// 每行一个 JSON 对象
+    Ndjson,
+}
+
+/// 导入的文章已存在（id 或 slug 相同）时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OnConflict {
+    /// 保留数据库中的文章
+    Skip,
+    /// 用导入的内容覆盖数据库中的文章
+    Overwrite,
+    /// 存在任何冲突时放弃整个导入
+    Fail,
+}
+
+#[derive(Debug, Default)]
+pub struct ImportSummary {
+    pub inserted: usize,
+    pub updated: usize,
+    pub skipped: usize,
+}
+
+// #region export
+/// 按 id 顺序逐行读取并写出所有文章，返回导出的数量
+///
+/// 默认用 `load_iter` 配合逐行加载模式，每次只从服务器取一行；指定 `fetch_size` 时改用服务端游标，
+/// 每次 `FETCH` 一批。两种方式导出大表时内存占用都不会随行数增长。
+pub fn export_posts<W: Write>(
+    conn: &mut PgConnection,
+    out: &mut W,
+    format: DumpFormat,
+    fetch_size: Option<i64>,
+) -> Result<usize, AppError> {
+    let query = posts::table.order(posts::id.asc()).select(Post::as_select());
+
+    let mut count = 0;
+    if format == DumpFormat::Json {
+        out.write_all(b"[")?;
+    }
+    let mut write_post = |post: Post| -> Result<(), AppError> {
+        match format {
+            DumpFormat::Json => {
+                out.write_all(if count == 0 { b"\n" } else { b",\n" })?;
+                serde_json::to_writer(&mut *out, &post).map_err(io_error)?;
+            }
+            DumpFormat::Ndjson => {
+                serde_json::to_writer(&mut *out, &post).map_err(io_error)?;
+                out.write_all(b"\n")?;
+            }
+        }
+        count += 1;
+        Ok(())
+    };
+    match fetch_size {
+        Some(fetch_size) => {
+            for_each_fetched(conn, query, fetch_size, write_post)?;
+        }
+        None => {
+            for post in query.load_iter::<Post, PgRowByRowLoadingMode>(conn)? {
+                write_post(post?)?;
+            }
+        }
+    }
+    if format == DumpFormat::Json {
+        out.write_all(b"\n]\n")?;
+    }
+    out.flush()?;
+
+    Ok(count)
+}
+// #endregion export
+
+// #region import
+/// 解析并校验全部记录后，在一个事务中写入
+///
+/// 以 `[` 开头的输入按 JSON 数组解析，否则按 NDJSON 解析。任何一条记录无法解析或未通过校验时，
+/// 不会写入任何数据。记录先按 id、再按 slug 匹配已有的文章，按 slug 匹配时保留数据库中的 id。
+pub fn import_posts(conn: &mut PgConnection, input: &str, on_conflict: OnConflict) -> Result<ImportSummary, AppError> {
+    let records = parse_records(input)?;
+    validate_records(&records)?;
+
+    conn.transaction(|conn| {
+        let ids: Vec<i32> = records.iter().map(|record| record.id).collect();
+        let slugs: Vec<&str> = records.iter().map(|record| record.slug.as_str()).collect();
+        let existing: Vec<(i32, String)> = posts::table
+            .filter(posts::id.eq_any(&ids).or(posts::slug.eq_any(&slugs)))
+            .select((posts::id, posts::slug))
+            .for_update()
+            .load(conn)?;
+        let targets = match_existing(&records, &existing)?;
+
+        if on_conflict == OnConflict::Fail && targets.iter().any(Option::is_some) {
+            let mut conflicts: Vec<_> = targets.iter().flatten().collect();
+            conflicts.sort();
+            let conflicts: Vec<_> = conflicts.iter().map(|id| id.to_string()).collect();
+            return Err(AppError::UniqueViolation(format!("文章已存在: {}", conflicts.join(", "))));
+        }
+
+        let mut summary = ImportSummary::default();
+        let mut new_records = Vec::new();
+        for (record, target) in records.iter().zip(&targets) {
+            match (target, on_conflict) {
+                (None, _) => new_records.push(record),
+                (Some(post_id), OnConflict::Overwrite) => {
+                    // AsChangeset 不包含主键，按 slug 匹配时 id 保持不变
+                    summary.updated += diesel::update(posts::table.find(post_id)).set(record).execute(conn)?;
+                }
+                (Some(_), _) => summary.skipped += 1,
+            }
+        }
+
+        for chunk in new_records.chunks(IMPORT_CHUNK_SIZE) {
+            summary.inserted += diesel::insert_into(posts::table).values(chunk.to_vec()).execute(conn)?;
+        }
+
+        // 显式写入 id 后序列不会前进，需要把它推到当前最大 id 之后，否则之后新建文章会主键冲突；
+        // 与序列当前的值取较大者，导入较小的 id 时序列不会后退
+        diesel::sql_query(
+            "SELECT setval(seq, GREATEST((SELECT MAX(id) FROM posts), pg_sequence_last_value(seq), 1)) \
+             FROM (SELECT pg_get_serial_sequence('posts', 'id')::regclass AS seq) s",
+        )
+        .execute(conn)?;
+
+        Ok(summary)
+    })
+}
+
+/// 为每条记录找到对应的已有文章：先按 id，再按 slug，没有对应的文章时为 `None`
+///
+/// id 与 slug 分别对应两篇不同的文章，或者多条记录对应同一篇文章时，无法确定要覆盖哪一篇，报告为冲突。
+fn match_existing(records: &[PostRecord], existing: &[(i32, String)]) -> Result<Vec<Option<i32>>, AppError> {
+    let ids: HashSet<i32> = existing.iter().map(|(id, _)| *id).collect();
+    let slug_owners: HashMap<&str, i32> = existing.iter().map(|(id, slug)| (slug.as_str(), *id)).collect();
+
+    let mut targets = Vec::with_capacity(records.len());
+    let mut claimed = HashMap::new();
+    let mut errors = Vec::new();
+    for record in records {
+        let by_id = ids.contains(&record.id).then_some(record.id);
+        let by_slug = slug_owners.get(record.slug.as_str()).copied();
+        let target = match (by_id, by_slug) {
+            (Some(id), Some(owner)) if id != owner => {
+                errors.push(format!("记录 {} 的 slug `{}` 已被文章 {} 使用", record.id, record.slug, owner));
+                None
+            }
+            (by_id, by_slug) => by_id.or(by_slug),
+        };
+        if let Some(post_id) = target
+            && let Some(other) = claimed.insert(post_id, record.id)
+        {
+            errors.push(format!("记录 {} 与记录 {} 对应同一篇文章 {}", other, record.id, post_id));
+        }
+        targets.push(target);
+    }
+
+    if errors.is_empty() {
+        Ok(targets)
+    } else {
+        Err(AppError::UniqueViolation(errors.join("\n")))
+    }
+}
+// #endregion import
+
+fn parse_records(input: &str) -> Result<Vec<PostRecord>, AppError> {
+    let mut records = Vec::new();
+    let mut errors = Vec::new();
+
+    if input.trim_start().starts_with('[') {
+        let values: Vec<serde_json::Value> =
+            serde_json::from_str(input).map_err(|e| AppError::Validation(format!("无法解析 JSON: {}", e)))?;
+        for (index, value) in values.into_iter().enumerate() {
+            match serde_json::from_value(value) {
+                Ok(record) => records.push(record),
+                Err(e) => errors.push(format!("第 {} 条记录: {}", index + 1, e)),
+            }
+        }
+    } else {
+        for (index, line) in input.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(line) {
+                Ok(record) => records.push(record),
+                Err(e) => errors.push(format!("第 {} 行: {}", index + 1, e)),
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(records)
+    } else {
+        Err(AppError::Validation(errors.join("\n")))
+    }
+}
+
+/// 检查数据库约束能发现的问题，一次性报告所有不合法的记录
+fn validate_records(records: &[PostRecord]) -> Result<(), AppError> {
+    let mut errors = Vec::new();
+    let mut seen = HashSet::new();
+    let mut seen_slugs = HashSet::new();
+
+    for record in records {
+        let mut problems = Vec::new();
+        if record.id <= 0 {
+            problems.push("id 必须为正数");
+        }
+        if !seen.insert(record.id) {
+            problems.push("id 重复");
+        }
+        if !record.slug.is_empty() && record.slug.bytes().all(|b| b.is_ascii_digit()) {
+            problems.push("slug 不能只包含数字，否则会被当作文章 id");
+        } else if !is_valid_slug(&record.slug) {
+            problems.push("slug 只能包含小写字母、数字和连字符");
+        }
+        if !seen_slugs.insert(record.slug.as_str()) {
+            problems.push("slug 重复");
+        }
+        if record.title.trim().is_empty() {
+            problems.push("标题不能为空");
+        }
+        if record.published != (record.state == PostState::Published) {
+            problems.push("published 与 state 不一致");
+        }
+        if record.state == PostState::Scheduled && record.publish_at.is_none() {
+            problems.push("scheduled 状态的文章必须设置 publish_at");
+        }
+        if record.updated_at < record.created_at {
+            problems.push("updated_at 早于 created_at");
+        }
+
+        if !problems.is_empty() {
+            errors.push(format!("文章 {}: {}", record.id, problems.join("，")));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::Validation(errors.join("\n")))
+    }
+}
+
+fn io_error(err: serde_json::Error) -> std::io::Error {
+    err.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: i32, slug: &str) -> String {
+        format!(
+            r#"{{"id":{},"title":"标题 {}","slug":"{}","body":"","published":false,"created_at":"2025-01-01T00:00:00Z","updated_at":"2025-01-01T00:00:00Z","state":"draft","publish_at":null,"body_format":"markdown"}}"#,
+            id, id, slug
+        )
+    }
+
+    fn validate(input: &str) -> Result<(), AppError> {
+        validate_records(&parse_records(input)?)
+    }
+
+    #[test]
+    fn accepts_valid_records() {
+        let input = format!("{}\n{}", record(1, "hello"), record(2, "post-123"));
+        assert!(validate(&input).is_ok());
+    }
+
+    #[test]
+    fn rejects_numeric_slug() {
+        let input = format!("[{},{}]", record(1, "hello"), record(7, "123"));
+        match validate(&input) {
+            Err(AppError::Validation(message)) => {
+                assert_eq!(message, "文章 7: slug 不能只包含数字，否则会被当作文章 id");
+            }
+            other => panic!("应当拒绝纯数字的 slug: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_slug() {
+        let input = record(3, "Hello World");
+        match validate(&input) {
+            Err(AppError::Validation(message)) => assert!(message.starts_with("文章 3: slug 只能包含"), "{}", message),
+            other => panic!("应当拒绝格式错误的 slug: {:?}", other),
+        }
+    }
+}
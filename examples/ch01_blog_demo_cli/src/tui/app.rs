@@ -0,0 +1,392 @@
+//! 终端界面的状态与按键处理，不涉及终端读写，可以直接用 `MemoryStore` 驱动
+use crate::models::{Post, UpdatePost};
+use crate::tui::store::PostStore;
+use ratatui::crossterm::event::KeyCode;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    List,
+    Detail,
+    /// 等待确认删除选中的文章
+    ConfirmDelete,
+}
+
+/// 需要由终端主循环完成的操作
+#[derive(Debug, PartialEq)]
+pub enum Effect {
+    None,
+    /// 暂停界面，用 `$EDITOR` 编辑这篇文章
+    Edit(Post),
+    Quit,
+}
+
+pub struct App<S: PostStore> {
+    store: S,
+    per_page: i64,
+    page: i64,
+    total: i64,
+    posts: Vec<Post>,
+    selected: usize,
+    mode: Mode,
+    /// 状态栏中展示的最近一次操作结果或错误
+    status: Option<String>,
+}
+
+impl<S: PostStore> App<S> {
+    pub fn new(store: S, per_page: i64) -> Self {
+        let mut app = App {
+            store,
+            per_page: per_page.max(1),
+            page: 0,
+            total: 0,
+            posts: Vec::new(),
+            selected: 0,
+            mode: Mode::List,
+            status: None,
+        };
+        app.reload();
+        app
+    }
+
+    pub fn posts(&self) -> &[Post] {
+        &self.posts
+    }
+
+    pub fn selected_post(&self) -> Option<&Post> {
+        self.posts.get(self.selected)
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn status(&self) -> Option<&str> {
+        self.status.as_deref()
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// 当前页码（从 0 开始）与总页数
+    pub fn page(&self) -> (i64, i64) {
+        (self.page, total_pages(self.total, self.per_page))
+    }
+
+    pub fn handle_key(&mut self, key: KeyCode) -> Effect {
+        match self.mode {
+            Mode::List => self.handle_list_key(key),
+            Mode::Detail => self.handle_detail_key(key),
+            Mode::ConfirmDelete => {
+                match key {
+                    KeyCode::Char('y') | KeyCode::Char('Y') => self.delete_selected(),
+                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                        self.mode = Mode::List;
+                        self.status = Some("已取消删除".into());
+                    }
+                    _ => {}
+                }
+                Effect::None
+            }
+        }
+    }
+
+    /// 保存编辑器中的修改，`changes` 没有任何字段时视为未修改
+    pub fn apply_edit(&mut self, post_id: i32, changes: UpdatePost) {
+        if changes == UpdatePost::default() {
+            self.status = Some("内容未修改".into());
+            return;
+        }
+        match self.store.update(post_id, &changes) {
+            Ok(post) => {
+                self.status = Some(format!("已保存文章 {}", post.id));
+                self.replace(post);
+            }
+            Err(e) => self.status = Some(format!("保存失败: {}", e)),
+        }
+    }
+
+    /// 编辑器无法启动等界面之外的错误也展示在状态栏
+    pub fn set_status(&mut self, status: impl Into<String>) {
+        self.status = Some(status.into());
+    }
+
+    fn handle_list_key(&mut self, key: KeyCode) -> Effect {
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return Effect::Quit,
+            KeyCode::Down | KeyCode::Char('j') => {
+                if self.selected + 1 < self.posts.len() {
+                    self.selected += 1;
+                }
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Right | KeyCode::PageDown | KeyCode::Char('l') => {
+                if self.page + 1 < total_pages(self.total, self.per_page) {
+                    self.page += 1;
+                    self.selected = 0;
+                    self.reload();
+                }
+            }
+            KeyCode::Left | KeyCode::PageUp | KeyCode::Char('h') => {
+                if self.page > 0 {
+                    self.page -= 1;
+                    self.selected = 0;
+                    self.reload();
+                }
+            }
+            KeyCode::Enter if self.selected_post().is_some() => self.mode = Mode::Detail,
+            _ => return self.handle_post_key(key),
+        }
+        Effect::None
+    }
+
+    fn handle_detail_key(&mut self, key: KeyCode) -> Effect {
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc | KeyCode::Backspace => {
+                self.mode = Mode::List;
+                Effect::None
+            }
+            _ => self.handle_post_key(key),
+        }
+    }
+
+    /// 列表与详情页共用的文章操作
+    fn handle_post_key(&mut self, key: KeyCode) -> Effect {
+        let Some(post) = self.selected_post() else {
+            return Effect::None;
+        };
+
+        match key {
+            KeyCode::Char('p') => {
+                let (post_id, published) = (post.id, !post.published);
+                match self.store.set_published(post_id, published) {
+                    Ok(post) => {
+                        let action = if post.published { "发布" } else { "取消发布" };
+                        self.status = Some(format!("已{}文章 {}", action, post.id));
+                        self.replace(post);
+                    }
+                    Err(e) => self.status = Some(format!("操作失败: {}", e)),
+                }
+                Effect::None
+            }
+            KeyCode::Char('e') => Effect::Edit(post.clone()),
+            KeyCode::Char('d') => {
+                self.mode = Mode::ConfirmDelete;
+                Effect::None
+            }
+            _ => Effect::None,
+        }
+    }
+
+    fn delete_selected(&mut self) {
+        self.mode = Mode::List;
+        let Some(post_id) = self.selected_post().map(|post| post.id) else {
+            return;
+        };
+
+        match self.store.delete(post_id) {
+            Ok(()) => {
+                self.status = Some(format!("已删除文章 {}", post_id));
+                self.reload();
+            }
+            Err(e) => self.status = Some(format!("删除失败: {}", e)),
+        }
+    }
+
+    /// 重新读取当前页，删除文章后当前页可能为空，此时退回上一页
+    fn reload(&mut self) {
+        match self.store.page(self.page, self.per_page) {
+            Ok((posts, total)) => {
+                self.total = total;
+                if posts.is_empty() && self.page > 0 {
+                    self.page = total_pages(total, self.per_page) - 1;
+                    return self.reload();
+                }
+                self.posts = posts;
+                self.selected = self.selected.min(self.posts.len().saturating_sub(1));
+            }
+            Err(e) => self.status = Some(format!("加载失败: {}", e)),
+        }
+    }
+
+    fn replace(&mut self, post: Post) {
+        if let Some(existing) = self.posts.iter_mut().find(|p| p.id == post.id) {
+            *existing = post;
+        }
+    }
+}
+
+/// 至少 1 页，没有文章时也能展示空列表
+fn total_pages(total: i64, per_page: i64) -> i64 {
+    if total <= 0 { 1 } else { (total - 1) / per_page.max(1) + 1 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::editor::parse_editor_text;
+    use crate::tui::store::MemoryStore;
+
+    fn app(count: i32, per_page: i64) -> App<MemoryStore> {
+        App::new(MemoryStore::with_sample_posts(count), per_page)
+    }
+
+    fn ids(app: &App<MemoryStore>) -> Vec<i32> {
+        app.posts().iter().map(|post| post.id).collect()
+    }
+
+    #[test]
+    fn pages_forward_and_back_within_bounds() {
+        let mut app = app(12, 5);
+        assert_eq!(app.page(), (0, 3));
+        assert_eq!(ids(&app), [1, 2, 3, 4, 5]);
+
+        app.handle_key(KeyCode::Down);
+        assert_eq!(app.selected(), 1);
+
+        app.handle_key(KeyCode::Right);
+        assert_eq!(app.page(), (1, 3));
+        assert_eq!(ids(&app), [6, 7, 8, 9, 10]);
+        assert_eq!(app.selected(), 0);
+
+        app.handle_key(KeyCode::Char('l'));
+        app.handle_key(KeyCode::Char('l'));
+        assert_eq!(app.page(), (2, 3));
+        assert_eq!(ids(&app), [11, 12]);
+
+        app.handle_key(KeyCode::Left);
+        app.handle_key(KeyCode::Left);
+        app.handle_key(KeyCode::Left);
+        assert_eq!(app.page(), (0, 3));
+    }
+
+    #[test]
+    fn huge_per_page_does_not_overflow() {
+        assert_eq!(total_pages(0, 5), 1);
+        assert_eq!(total_pages(10, 5), 2);
+        assert_eq!(total_pages(11, 5), 3);
+        assert_eq!(total_pages(10, i64::MAX), 1);
+
+        let mut app = app(3, i64::MAX);
+        assert_eq!(app.page(), (0, 1));
+        assert_eq!(ids(&app), [1, 2, 3]);
+        app.handle_key(KeyCode::Right);
+        assert_eq!(app.page(), (0, 1));
+    }
+
+    #[test]
+    fn selection_stays_on_current_page() {
+        let mut app = app(3, 5);
+        app.handle_key(KeyCode::Up);
+        assert_eq!(app.selected(), 0);
+        for _ in 0..5 {
+            app.handle_key(KeyCode::Char('j'));
+        }
+        assert_eq!(app.selected(), 2);
+    }
+
+    #[test]
+    fn toggles_publish_state() {
+        let mut app = app(2, 5);
+        assert!(!app.selected_post().unwrap().published);
+
+        assert_eq!(app.handle_key(KeyCode::Char('p')), Effect::None);
+        assert!(app.selected_post().unwrap().published);
+        assert!(app.store().posts()[0].published);
+        assert_eq!(app.status(), Some("已发布文章 1"));
+
+        app.handle_key(KeyCode::Enter);
+        assert_eq!(app.mode(), Mode::Detail);
+        app.handle_key(KeyCode::Char('p'));
+        assert!(!app.store().posts()[0].published);
+        assert_eq!(app.status(), Some("已取消发布文章 1"));
+    }
+
+    #[test]
+    fn delete_requires_confirmation() {
+        let mut app = app(3, 5);
+        app.handle_key(KeyCode::Down);
+
+        app.handle_key(KeyCode::Char('d'));
+        assert_eq!(app.mode(), Mode::ConfirmDelete);
+        // 确认框中其他按键不生效
+        app.handle_key(KeyCode::Char('p'));
+        assert_eq!(app.mode(), Mode::ConfirmDelete);
+        assert!(app.store().posts()[1].published);
+
+        app.handle_key(KeyCode::Char('n'));
+        assert_eq!(app.mode(), Mode::List);
+        assert_eq!(app.status(), Some("已取消删除"));
+        assert_eq!(app.store().posts().len(), 3);
+
+        app.handle_key(KeyCode::Char('d'));
+        app.handle_key(KeyCode::Char('y'));
+        assert_eq!(app.mode(), Mode::List);
+        assert_eq!(app.status(), Some("已删除文章 2"));
+        assert_eq!(ids(&app), [1, 3]);
+        assert_eq!(app.store().posts().len(), 2);
+    }
+
+    #[test]
+    fn deleting_last_post_on_page_goes_back_a_page() {
+        let mut app = app(6, 5);
+        app.handle_key(KeyCode::Right);
+        assert_eq!(ids(&app), [6]);
+
+        app.handle_key(KeyCode::Char('d'));
+        app.handle_key(KeyCode::Char('Y'));
+        assert_eq!(app.page(), (0, 1));
+        assert_eq!(ids(&app), [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn saves_edit_changeset() {
+        let mut app = app(2, 5);
+        let Effect::Edit(post) = app.handle_key(KeyCode::Char('e')) else {
+            panic!("按 e 应当进入编辑");
+        };
+
+        let changes = parse_editor_text(&post, "新的标题\n\n示例文章的正文。\n").unwrap();
+        assert_eq!(changes.title.as_deref(), Some("新的标题"));
+        app.apply_edit(post.id, changes);
+
+        assert_eq!(app.status(), Some("已保存文章 1"));
+        assert_eq!(app.selected_post().unwrap().title, "新的标题");
+        let stored = &app.store().posts()[0];
+        assert_eq!(stored.title, "新的标题");
+        assert_eq!(stored.body, "示例文章的正文。");
+    }
+
+    #[test]
+    fn unchanged_edit_is_not_saved() {
+        let mut app = app(1, 5);
+        let post = app.selected_post().unwrap().clone();
+        let before = post.updated_at;
+
+        let changes = parse_editor_text(&post, &crate::tui::editor::to_editor_text(&post)).unwrap();
+        app.apply_edit(post.id, changes);
+
+        assert_eq!(app.status(), Some("内容未修改"));
+        assert_eq!(app.store().posts()[0].updated_at, before);
+    }
+
+    #[test]
+    fn store_errors_are_shown_in_status() {
+        let mut app = app(1, 5);
+        app.apply_edit(42, UpdatePost { title: Some("不存在".into()), body: None });
+        assert!(app.status().unwrap().starts_with("保存失败"));
+    }
+
+    #[test]
+    fn quits_from_list() {
+        let mut app = app(1, 5);
+        app.handle_key(KeyCode::Enter);
+        assert_eq!(app.handle_key(KeyCode::Esc), Effect::None);
+        assert_eq!(app.mode(), Mode::List);
+        assert_eq!(app.handle_key(KeyCode::Char('q')), Effect::Quit);
+    }
+}
@@ -0,0 +1,23 @@
+//! 在 `$EDITOR` 中编辑文章时使用的文本格式：第一行为标题，空一行后为正文
+use crate::models::{Post, UpdatePost};
+use common::AppError;
+
+pub fn to_editor_text(post: &Post) -> String {
+    format!("{}\n\n{}\n", post.title, post.body)
+}
+
+/// 解析编辑后的文本，只保留与原文章不同的字段
+pub fn parse_editor_text(post: &Post, text: &str) -> Result<UpdatePost, AppError> {
+    let (title, body) = text.split_once('\n').unwrap_or((text, ""));
+    let title = title.trim();
+    let body = body.trim_matches('\n');
+
+    if title.is_empty() {
+        return Err(AppError::Validation("标题不能为空".into()));
+    }
+
+    Ok(UpdatePost {
+        title: (title != post.title).then(|| title.to_string()),
+        body: (body != post.body).then(|| body.to_string()),
+    })
+}
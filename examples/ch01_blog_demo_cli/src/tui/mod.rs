@@ -0,0 +1,8 @@
+//! `blog tui` 的终端界面
+//!
+//! 界面状态（`app`）、绘制（`ui`）与数据读写（`store`）相互分离，
+//! 按键处理与绘制都不依赖真实终端，可以配合 `MemoryStore` 与 ratatui 的 `TestBackend` 验证。
+pub mod app;
+pub mod editor;
+pub mod store;
+pub mod ui;
@@ -0,0 +1,140 @@
+//! 终端界面读写文章的接口
+//!
+//! 界面只依赖 `PostStore`，连接数据库时使用 `DbStore`，演示或测试时使用不需要数据库的 `MemoryStore`。
+use crate::body_format::BodyFormat;
+use crate::models::{Post, UpdatePost};
+use crate::post_state::PostState;
+use crate::posts;
+use crate::slug::slugify_title;
+use chrono::Utc;
+use common::AppError;
+use diesel::PgConnection;
+
+pub trait PostStore {
+    /// 按 id 顺序读取第 `page` 页（从 0 开始），同时返回文章总数
+    fn page(&mut self, page: i64, per_page: i64) -> Result<(Vec<Post>, i64), AppError>;
+    fn set_published(&mut self, post_id: i32, published: bool) -> Result<Post, AppError>;
+    fn update(&mut self, post_id: i32, changes: &UpdatePost) -> Result<Post, AppError>;
+    fn delete(&mut self, post_id: i32) -> Result<(), AppError>;
+}
+
+pub struct DbStore {
+    conn: PgConnection,
+}
+
+impl DbStore {
+    pub fn new(conn: PgConnection) -> Self {
+        DbStore { conn }
+    }
+}
+
+impl PostStore for DbStore {
+    fn page(&mut self, page: i64, per_page: i64) -> Result<(Vec<Post>, i64), AppError> {
+        Ok(posts::page_posts(&mut self.conn, page, per_page)?)
+    }
+
+    fn set_published(&mut self, post_id: i32, published: bool) -> Result<Post, AppError> {
+        posts::set_published(&mut self.conn, post_id, published)?.ok_or_else(|| not_found(post_id))
+    }
+
+    fn update(&mut self, post_id: i32, changes: &UpdatePost) -> Result<Post, AppError> {
+        posts::update_post(&mut self.conn, post_id, changes)?.ok_or_else(|| not_found(post_id))
+    }
+
+    fn delete(&mut self, post_id: i32) -> Result<(), AppError> {
+        if posts::delete_post(&mut self.conn, post_id)? { Ok(()) } else { Err(not_found(post_id)) }
+    }
+}
+
+/// 保存在内存中的文章，用于 `blog tui --demo` 以及脱离数据库测试界面逻辑
+#[derive(Default)]
+pub struct MemoryStore {
+    posts: Vec<Post>,
+}
+
+impl MemoryStore {
+    pub fn new(posts: Vec<Post>) -> Self {
+        MemoryStore { posts }
+    }
+
+    /// 生成 `count` 篇示例文章，偶数 id 的文章为已发布
+    pub fn with_sample_posts(count: i32) -> Self {
+        let now = Utc::now();
+        let posts = (1..=count)
+            .map(|id| {
+                let title = format!("示例文章 {}", id);
+                let published = id % 2 == 0;
+                Post {
+                    id,
+                    slug: slugify_title(&title),
+                    title,
+                    body: format!("这是第 {} 篇示例文章的正文。", id),
+                    published,
+                    created_at: now,
+                    updated_at: now,
+                    state: if published { PostState::Published } else { PostState::Draft },
+                    publish_at: None,
+                    body_format: BodyFormat::Plain,
+                }
+            })
+            .collect();
+        MemoryStore { posts }
+    }
+
+    pub fn posts(&self) -> &[Post] {
+        &self.posts
+    }
+
+    fn find_mut(&mut self, post_id: i32) -> Result<&mut Post, AppError> {
+        self.posts.iter_mut().find(|post| post.id == post_id).ok_or_else(|| not_found(post_id))
+    }
+}
+
+impl PostStore for MemoryStore {
+    fn page(&mut self, page: i64, per_page: i64) -> Result<(Vec<Post>, i64), AppError> {
+        self.posts.sort_by_key(|post| post.id);
+        // 页码或每页数量过大时跳过所有文章，而不是溢出或截断为较小的值
+        let skip = usize::try_from(page.saturating_mul(per_page)).unwrap_or(usize::MAX);
+        let take = usize::try_from(per_page).unwrap_or(0);
+        let items = self
+            .posts
+            .iter()
+            .skip(skip)
+            .take(take)
+            .cloned()
+            .collect();
+        Ok((items, self.posts.len() as i64))
+    }
+
+    fn set_published(&mut self, post_id: i32, published: bool) -> Result<Post, AppError> {
+        let post = self.find_mut(post_id)?;
+        post.published = published;
+        post.state = if published { PostState::Published } else { PostState::Draft };
+        post.publish_at = None;
+        post.updated_at = Utc::now();
+        Ok(post.clone())
+    }
+
+    fn update(&mut self, post_id: i32, changes: &UpdatePost) -> Result<Post, AppError> {
+        let post = self.find_mut(post_id)?;
+        if let Some(title) = &changes.title {
+            post.slug = slugify_title(title);
+            post.title = title.clone();
+        }
+        if let Some(body) = &changes.body {
+            post.body = body.clone();
+        }
+        post.updated_at = Utc::now();
+        Ok(post.clone())
+    }
+
+    fn delete(&mut self, post_id: i32) -> Result<(), AppError> {
+        let before = self.posts.len();
+        self.posts.retain(|post| post.id != post_id);
+        if self.posts.len() < before { Ok(()) } else { Err(not_found(post_id)) }
+    }
+}
+
+fn not_found(post_id: i32) -> AppError {
+    AppError::NotFound(format!("文章 {}", post_id))
+}
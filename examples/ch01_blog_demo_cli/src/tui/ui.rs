@@ -0,0 +1,184 @@
+//! 根据 `App` 的状态绘制界面，任何 ratatui 后端都可以使用，包括测试用的 `TestBackend`
+use crate::tui::app::{App, Mode};
+use crate::tui::store::PostStore;
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use ratatui::style::{Modifier, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Clear, Paragraph, Row, Table, TableState, Wrap};
+use ratatui::Frame;
+
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+pub fn draw<S: PostStore>(frame: &mut Frame, app: &App<S>) {
+    let [main, status, help] =
+        Layout::vertical([Constraint::Min(1), Constraint::Length(1), Constraint::Length(1)]).areas(frame.area());
+
+    match app.mode() {
+        Mode::List => draw_list(frame, app, main),
+        Mode::Detail => draw_detail(frame, app, main),
+        Mode::ConfirmDelete => {
+            draw_list(frame, app, main);
+            draw_confirm(frame, app, main);
+        }
+    }
+
+    frame.render_widget(Paragraph::new(app.status().unwrap_or_default()), status);
+    frame.render_widget(Paragraph::new(help_text(app.mode())).dim(), help);
+}
+
+fn draw_list<S: PostStore>(frame: &mut Frame, app: &App<S>, area: Rect) {
+    let (page, pages) = app.page();
+    let rows = app.posts().iter().map(|post| {
+        Row::new(vec![
+            post.id.to_string(),
+            post.state.to_string(),
+            post.updated_at.format(TIME_FORMAT).to_string(),
+            post.title.clone(),
+        ])
+    });
+    let widths = [Constraint::Length(6), Constraint::Length(10), Constraint::Length(16), Constraint::Fill(1)];
+    let table = Table::new(rows, widths)
+        .header(Row::new(vec!["ID", "状态", "更新时间", "标题"]).bold())
+        .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+        .block(Block::bordered().title(format!(" 文章 第 {}/{} 页 ", page + 1, pages)));
+
+    let mut state = TableState::default().with_selected(app.selected_post().map(|_| app.selected()));
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn draw_detail<S: PostStore>(frame: &mut Frame, app: &App<S>, area: Rect) {
+    let Some(post) = app.selected_post() else {
+        return;
+    };
+
+    let mut lines = vec![
+        Line::from(post.title.clone()).bold(),
+        Line::from(format!("slug: {}  状态: {}  正文格式: {}", post.slug, post.state, post.body_format)).dim(),
+        Line::from(format!(
+            "创建: {}  更新: {}",
+            post.created_at.format(TIME_FORMAT),
+            post.updated_at.format(TIME_FORMAT)
+        ))
+        .dim(),
+        Line::default(),
+    ];
+    lines.extend(post.body.lines().map(|line| Line::from(line.to_string())));
+
+    let detail = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(Block::bordered().title(format!(" 文章 {} ", post.id)));
+    frame.render_widget(detail, area);
+}
+
+fn draw_confirm<S: PostStore>(frame: &mut Frame, app: &App<S>, area: Rect) {
+    let Some(post) = app.selected_post() else {
+        return;
+    };
+
+    let [popup] = Layout::vertical([Constraint::Length(5)]).flex(Flex::Center).areas(area);
+    let [popup] = Layout::horizontal([Constraint::Percentage(60)]).flex(Flex::Center).areas(popup);
+
+    let text = vec![Line::from(format!("确定删除「{}」吗？", post.title)), Line::default(), Line::from("y 确认 / n 取消")];
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(text).centered().block(Block::bordered().title(" 删除文章 ")), popup);
+}
+
+fn help_text(mode: Mode) -> &'static str {
+    match mode {
+        Mode::List => "↑↓ 选择  ←→ 翻页  Enter 详情  p 发布/取消发布  e 编辑  d 删除  q 退出",
+        Mode::Detail => "p 发布/取消发布  e 编辑  d 删除  Esc 返回",
+        Mode::ConfirmDelete => "y 确认删除  n 取消",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::store::MemoryStore;
+    use ratatui::backend::TestBackend;
+    use ratatui::crossterm::event::KeyCode;
+    use ratatui::text::Span;
+    use ratatui::Terminal;
+
+    /// 把绘制结果转换为每行一个字符串，宽字符之后的占位格不输出
+    fn render(app: &App<MemoryStore>) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(80, 12)).unwrap();
+        terminal.draw(|frame| draw(frame, app)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                let mut line = String::new();
+                let mut skip = 0;
+                for x in 0..buffer.area.width {
+                    if skip > 0 {
+                        skip -= 1;
+                        continue;
+                    }
+                    let symbol = buffer[(x, y)].symbol();
+                    skip = Span::raw(symbol).width().saturating_sub(1);
+                    line.push_str(symbol);
+                }
+                line
+            })
+            .collect()
+    }
+
+    fn contains(lines: &[String], text: &str) -> bool {
+        lines.iter().any(|line| line.contains(text))
+    }
+
+    #[test]
+    fn draws_list_with_page_and_help() {
+        let app = App::new(MemoryStore::with_sample_posts(12), 5);
+        let lines = render(&app);
+
+        assert!(lines[0].contains("文章 第 1/3 页"));
+        assert!(contains(&lines, "示例文章 1"));
+        assert!(contains(&lines, "示例文章 5"));
+        assert!(!contains(&lines, "示例文章 6"));
+        assert!(contains(&lines, "draft"));
+        assert!(contains(&lines, "published"));
+        assert!(lines[11].starts_with("↑↓ 选择"));
+    }
+
+    #[test]
+    fn highlights_selected_row() {
+        let mut app = App::new(MemoryStore::with_sample_posts(3), 5);
+        app.handle_key(KeyCode::Down);
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 12)).unwrap();
+        terminal.draw(|frame| draw(frame, &app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        // 边框占一行、表头占一行，第二篇文章在第 3 行
+        assert!(buffer[(1, 3)].modifier.contains(Modifier::REVERSED));
+        assert!(!buffer[(1, 2)].modifier.contains(Modifier::REVERSED));
+    }
+
+    #[test]
+    fn draws_detail() {
+        let mut app = App::new(MemoryStore::with_sample_posts(3), 5);
+        app.handle_key(KeyCode::Enter);
+        let lines = render(&app);
+
+        assert!(lines[0].contains("文章 1"));
+        assert!(contains(&lines, "slug: "));
+        assert!(contains(&lines, "这是第 1 篇示例文章的正文。"));
+        assert!(lines[11].contains("Esc 返回"));
+    }
+
+    #[test]
+    fn draws_confirm_dialog_and_status() {
+        let mut app = App::new(MemoryStore::with_sample_posts(3), 5);
+        app.handle_key(KeyCode::Char('d'));
+        let lines = render(&app);
+        assert!(contains(&lines, "删除文章"));
+        assert!(contains(&lines, "确定删除「示例文章 1」吗？"));
+        assert!(lines[11].contains("y 确认删除"));
+
+        app.handle_key(KeyCode::Char('y'));
+        let lines = render(&app);
+        assert!(!contains(&lines, "确定删除"));
+        assert_eq!(lines[10].trim_end(), "已删除文章 1");
+    }
+}
@@ -1,10 +1,12 @@
 use actix_web::{web, App, HttpServer};
+use std::io;
 mod pool;
 use pool::create_db_pool;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    let pool = create_db_pool()?;
+    common::query_log::init_tracing();
+    let pool = create_db_pool().map_err(io::Error::other)?;
 
     HttpServer::new(move || {
         App::new()
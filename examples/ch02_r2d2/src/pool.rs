@@ -1,21 +1,37 @@
+use common::{database_url, migrations, query_log, AppError};
 use diesel::pg::PgConnection;
-use diesel::r2d2::{self, ConnectionManager};
-use std::env;
-use std::io;
-use dotenvy::dotenv;
+use diesel::r2d2::{self, ConnectionManager, CustomizeConnection};
+use diesel_migrations::{embed_migrations, EmbeddedMigrations};
 
 type DbPool = r2d2::Pool<ConnectionManager<PgConnection>>;
 
-pub fn create_db_pool() -> io::Result<DbPool> {
-    dotenv().ok(); // 加载 .env 文件，忽略加载失败
-    let database_url = env::var("DATABASE_URL")
-    .map_err(|e| io::Error::new(io::ErrorKind::NotFound, format!("DATABASE_URL missing: {}", e)))?;
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
 
-    let manager = ConnectionManager::<PgConnection>::new(database_url);
+/// 本示例在数据库中使用的 schema，与其他章节的表和迁移记录互不影响
+pub const SCHEMA: &str = env!("CARGO_PKG_NAME");
+
+/// 连接池中的每个连接在建立后都切换到本示例的 schema
+#[derive(Debug)]
+struct UseSchema;
+
+impl CustomizeConnection<PgConnection, r2d2::Error> for UseSchema {
+    fn on_acquire(&self, conn: &mut PgConnection) -> Result<(), r2d2::Error> {
+        migrations::use_schema(conn, SCHEMA).map_err(r2d2::Error::QueryError)
+    }
+}
+
+pub fn create_db_pool() -> Result<DbPool, AppError> {
+    // 默认 instrumentation 需要在连接池建立连接之前注册
+    query_log::install_from_env();
+    let manager = ConnectionManager::<PgConnection>::new(database_url()?);
     let pool = r2d2::Pool::builder()
     .max_size(10)
-    .build(manager)
-    .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("Failed to build pool: {}", e)))?;
+    .connection_customizer(Box::new(UseSchema))
+    .build(manager)?;
+
+    // 启动时先执行尚未执行的迁移，再对外提供连接
+    let mut conn = pool.get()?;
+    migrations::run_pending(&mut conn, &MIGRATIONS)?;
 
     Ok(pool)
-}
\ No newline at end of file
+}
@@ -0,0 +1,14 @@
+use ch03_usage_read::establish_connection;
+use ch03_usage_read::post_filter::PostFilter;
+use common::Explain;
+
+/// 用法：`explain 'title_contains=p&sort=title:desc'`，参数与 query_filter 相同
+fn main() {
+    let filter = PostFilter::from_query_string(&std::env::args().nth(1).unwrap_or_default())
+        .unwrap_or_else(|e| panic!("{}", e));
+
+    let connection = &mut establish_connection();
+
+    let plan = filter.into_query().explain(connection).unwrap_or_else(|e| panic!("{}", e)); // [!code focus:2]
+    print!("{}", plan);
+}
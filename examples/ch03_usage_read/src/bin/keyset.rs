@@ -0,0 +1,32 @@
+use ch03_usage_read::keyset::{self, Direction, KeysetOptions, SortKey};
+use ch03_usage_read::{establish_connection, schema};
+
+/// 用法：`keyset [next|prev] [游标]`，游标取自上一次输出的 next / prev
+fn main() {
+    use diesel::prelude::*;
+    use schema::posts::dsl::{posts, published};
+
+    let mut args = std::env::args().skip(1);
+    let direction = match args.next().as_deref() {
+        None | Some("next") => Direction::Next,
+        Some("prev") => Direction::Prev,
+        Some(other) => panic!("未知的翻页方向: {}", other),
+    };
+    let cursor = args.next();
+
+    let connection = &mut establish_connection();
+
+    // 先组合好过滤条件，排序交给 keyset 分页
+    let query = posts.filter(published.eq(true)).into_boxed();
+    let options = KeysetOptions { sort: SortKey::Title, descending: false, per_page: 3 };
+
+    let page = keyset::load_page(connection, query, options, cursor.as_deref(), direction) // [!code focus]
+        .unwrap_or_else(|e| panic!("{}", e));
+
+    for post in &page.items {
+        println!("标题：{}", post.title);
+        println!("------------------");
+    }
+    println!("next: {}", page.next.as_deref().unwrap_or("-"));
+    println!("prev: {}", page.prev.as_deref().unwrap_or("-"));
+}
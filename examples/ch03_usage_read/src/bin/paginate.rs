@@ -0,0 +1,26 @@
+use ch03_usage_read::{establish_connection, models, schema};
+use common::Paginate;
+
+fn main() {
+    use diesel::prelude::*;
+    use models::Post;
+    use schema::posts::dsl::{id, posts, published};
+
+    let connection = &mut establish_connection();
+
+    // boxed 查询同样可以分页
+    let query = posts.filter(published.eq(true)).order(id.asc()).into_boxed();
+
+    let page = query // [!code focus:5]
+        .select(Post::as_select())
+        .paginate(2)
+        .per_page(3)
+        .load_page::<Post>(connection)
+        .expect("Error loading posts");
+
+    println!("第 {}/{} 页，共 {} 篇文章", page.page, page.total_pages, page.total);
+    for post in page.items {
+        println!("标题：{}", post.title);
+        println!("------------------");
+    }
+}
@@ -0,0 +1,21 @@
+use ch03_usage_read::{establish_connection, report};
+use common::report::{write_report, ReportFormat};
+use std::io;
+
+/// 用法：`post_report [--csv]`
+fn main() {
+    let format = match std::env::args().nth(1).as_deref() {
+        Some("--csv") => ReportFormat::Csv,
+        _ => ReportFormat::Table,
+    };
+
+    let connection = &mut establish_connection();
+    let out = &mut io::stdout().lock();
+
+    let by_state = report::posts_by_state(connection).expect("Error loading report"); // [!code focus:2]
+    let lengths = report::body_length_stats(connection, 1).expect("Error loading report");
+
+    write_report(out, &by_state, format).unwrap_or_else(|e| panic!("{}", e));
+    println!();
+    write_report(out, &lengths, format).unwrap_or_else(|e| panic!("{}", e));
+}
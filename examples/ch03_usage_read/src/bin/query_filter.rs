@@ -0,0 +1,29 @@
+use ch03_usage_read::post_filter::PostFilter;
+use ch03_usage_read::{establish_connection, models};
+
+/// 用法：
+/// `query_filter 'title_contains=p&published=true&sort=title:desc&limit=5'`
+/// `query_filter --title-contains p --published true --sort title:desc --limit 5`
+fn main() {
+    use diesel::prelude::*;
+    use models::Post;
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let filter = match args.first() {
+        Some(arg) if !arg.starts_with("--") => PostFilter::from_query_string(arg),
+        _ => PostFilter::from_args(args),
+    }
+    .unwrap_or_else(|e| panic!("{}", e));
+
+    let connection = &mut establish_connection();
+
+    let results = filter // [!code focus:4]
+        .into_query()
+        .select(Post::as_select())
+        .load(connection)
+        .expect("Error loading posts");
+
+    for post in results {
+        println!("{:>3}  {}  {}", post.id, if post.published { "已发布" } else { "草稿  " }, post.title);
+    }
+}
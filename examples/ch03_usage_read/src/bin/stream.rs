@@ -0,0 +1,27 @@
+use ch03_usage_read::stream::{self, StreamMode};
+use ch03_usage_read::{establish_connection, schema};
+use std::io::{self, BufWriter};
+
+/// 用法：`stream [row|cursor] [fetch_size]`，把已发布的文章以 NDJSON 写到标准输出
+fn main() {
+    use diesel::prelude::*;
+    use schema::posts::dsl::{id, posts, published};
+
+    let mut args = std::env::args().skip(1);
+    let mode = match args.next().as_deref() {
+        None | Some("cursor") => {
+            let fetch_size = args.next().map(|n| n.parse().expect("fetch_size 应为整数"));
+            StreamMode::Cursor { fetch_size: fetch_size.unwrap_or(common::cursor::DEFAULT_FETCH_SIZE) }
+        }
+        Some("row") => StreamMode::RowByRow,
+        Some(other) => panic!("未知的读取方式: {}", other),
+    };
+
+    let connection = &mut establish_connection();
+
+    let query = posts.filter(published.eq(true)).order(id.asc()).into_boxed();
+    let out = &mut BufWriter::new(io::stdout().lock());
+
+    let count = stream::write_ndjson(connection, query, mode, out).unwrap_or_else(|e| panic!("{}", e)); // [!code focus]
+    eprintln!("已导出 {} 篇文章", count);
+}
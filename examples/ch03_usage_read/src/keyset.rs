@@ -0,0 +1,178 @@
+//! 基于游标（keyset）的分页
+//!
+//! OFFSET 分页需要数据库扫描并丢弃前面所有的行，页码越大越慢；翻页期间有新文章插入时，
+//! 还会出现重复或遗漏。keyset 分页记住上一页最后一行的排序键，下一页直接从这里继续：
+//! `WHERE (created_at, id) > ($1, $2) ORDER BY created_at, id LIMIT $3`。
+//! 排序键末尾总是附加唯一的 `id`，保证顺序稳定。
+use crate::models::Post;
+use crate::schema::posts;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use common::AppError;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// 每页最多的行数
+pub const MAX_PER_PAGE: i64 = 100;
+
+/// 分页使用的排序键
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortKey {
+    /// `(created_at, id)`
+    CreatedAt,
+    /// `(title, id)`
+    Title,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Next,
+    Prev,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct KeysetOptions {
+    pub sort: SortKey,
+    pub descending: bool,
+    pub per_page: i64,
+}
+
+pub struct CursorPage {
+    pub items: Vec<Post>,
+    /// 下一页的游标，已经是最后一页时为 `None`
+    pub next: Option<String>,
+    /// 上一页的游标，已经是第一页时为 `None`
+    pub prev: Option<String>,
+}
+
+/// 游标中保存的排序键取值，对调用方不透明
+#[derive(Debug, Serialize, Deserialize)]
+struct Cursor {
+    sort: SortKey,
+    descending: bool,
+    key: KeyValue,
+    id: i32,
+}
+
+/// 带标签序列化（如 `{"text": "..."}`），内容恰好是合法时间的标题不会被误认为时间
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum KeyValue {
+    Time(DateTime<Utc>),
+    Text(String),
+}
+
+// #region load_page
+/// 在 `query` 的基础上按 `options` 排序并读取一页
+///
+/// `query` 可以事先加好任意过滤条件，如 `posts::table.filter(posts::published.eq(true)).into_boxed()`，
+/// 排序会被这里的排序键覆盖。`cursor` 为 `None` 时读取第一页（`Direction::Prev` 时为最后一页）。
+pub fn load_page<'a>(
+    conn: &mut PgConnection,
+    query: posts::BoxedQuery<'a, Pg>,
+    options: KeysetOptions,
+    cursor: Option<&str>,
+    direction: Direction,
+) -> Result<CursorPage, AppError> {
+    if !(1..=MAX_PER_PAGE).contains(&options.per_page) {
+        return Err(AppError::Validation(format!(
+            "per_page 应为 1 到 {} 之间的整数，实际为 {}",
+            MAX_PER_PAGE, options.per_page
+        )));
+    }
+
+    let cursor = cursor.map(|c| decode(c, options)).transpose()?;
+    let from_cursor = cursor.is_some();
+
+    // 向前翻页时按相反的顺序读取紧邻游标之前的行，读完再反转回来
+    let descending = options.descending != (direction == Direction::Prev);
+    let mut query = order(query, options.sort, descending);
+    if let Some(cursor) = cursor {
+        query = after(query, cursor, descending)?;
+    }
+
+    // 多读一行用来判断这个方向上是否还有数据
+    let mut items = query
+        .limit(options.per_page + 1)
+        .select(Post::as_select())
+        .load::<Post>(conn)?;
+    let has_more = items.len() as i64 > options.per_page;
+    items.truncate(options.per_page as usize);
+    if direction == Direction::Prev {
+        items.reverse();
+    }
+
+    // 从游标出发时，来的方向上一定还有数据
+    let (has_next, has_prev) = match direction {
+        Direction::Next => (has_more, from_cursor),
+        Direction::Prev => (from_cursor, has_more),
+    };
+
+    Ok(CursorPage {
+        next: items.last().filter(|_| has_next).map(|post| encode(post, options)),
+        prev: items.first().filter(|_| has_prev).map(|post| encode(post, options)),
+        items,
+    })
+}
+// #endregion load_page
+
+fn order<'a>(query: posts::BoxedQuery<'a, Pg>, sort: SortKey, descending: bool) -> posts::BoxedQuery<'a, Pg> {
+    match (sort, descending) {
+        (SortKey::CreatedAt, false) => query.order((posts::created_at.asc(), posts::id.asc())),
+        (SortKey::CreatedAt, true) => query.order((posts::created_at.desc(), posts::id.desc())),
+        (SortKey::Title, false) => query.order((posts::title.asc(), posts::id.asc())),
+        (SortKey::Title, true) => query.order((posts::title.desc(), posts::id.desc())),
+    }
+}
+
+/// 只保留按当前顺序排在游标之后的行，即 `(key, id) > (cursor.key, cursor.id)`（降序时为 `<`）
+fn after<'a>(query: posts::BoxedQuery<'a, Pg>, cursor: Cursor, descending: bool) -> Result<posts::BoxedQuery<'a, Pg>, AppError> {
+    let id = cursor.id;
+    let query = match (cursor.sort, cursor.key, descending) {
+        (SortKey::CreatedAt, KeyValue::Time(key), false) => {
+            query.filter(posts::created_at.gt(key).or(posts::created_at.eq(key).and(posts::id.gt(id))))
+        }
+        (SortKey::CreatedAt, KeyValue::Time(key), true) => {
+            query.filter(posts::created_at.lt(key).or(posts::created_at.eq(key).and(posts::id.lt(id))))
+        }
+        (SortKey::Title, KeyValue::Text(key), false) => {
+            query.filter(posts::title.gt(key.clone()).or(posts::title.eq(key).and(posts::id.gt(id))))
+        }
+        (SortKey::Title, KeyValue::Text(key), true) => {
+            query.filter(posts::title.lt(key.clone()).or(posts::title.eq(key).and(posts::id.lt(id))))
+        }
+        _ => return Err(invalid_cursor()),
+    };
+    Ok(query)
+}
+
+fn encode(post: &Post, options: KeysetOptions) -> String {
+    let key = match options.sort {
+        SortKey::CreatedAt => KeyValue::Time(post.created_at),
+        SortKey::Title => KeyValue::Text(post.title.clone()),
+    };
+    let cursor = Cursor { sort: options.sort, descending: options.descending, key, id: post.id };
+    let json = serde_json::to_vec(&cursor).expect("游标总能序列化为 JSON");
+    URL_SAFE_NO_PAD.encode(json)
+}
+
+/// 解码游标，游标必须由相同的排序方式生成
+fn decode(encoded: &str, options: KeysetOptions) -> Result<Cursor, AppError> {
+    let cursor: Cursor = URL_SAFE_NO_PAD
+        .decode(encoded)
+        .ok()
+        .and_then(|json| serde_json::from_slice(&json).ok())
+        .ok_or_else(invalid_cursor)?;
+
+    if cursor.sort != options.sort || cursor.descending != options.descending {
+        return Err(AppError::Validation("游标与当前的排序方式不一致".into()));
+    }
+    Ok(cursor)
+}
+
+fn invalid_cursor() -> AppError {
+    AppError::Validation("无效的分页游标".into())
+}
@@ -0,0 +1,252 @@
+//! 根据查询参数动态组合文章查询
+//!
+//! 同一份过滤条件既可以来自 URL 查询字符串，也可以来自命令行参数：
+//!
+//! ```text
+//! title_contains=diesel&published=true&id_in=1,2,3&created_between=2025-05-01,2025-06-01&sort=title:asc,id:desc&limit=20
+//! --title-contains diesel --published true --sort title:asc,id:desc --limit 20
+//! ```
+//!
+//! 字段名和排序列都来自白名单，未知字段直接报错，不会被拼接进 SQL。
+use crate::schema::posts;
+use chrono::{DateTime, NaiveDate, Utc};
+use common::AppError;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+
+/// 没有指定 `limit` 时最多返回的行数
+pub const DEFAULT_LIMIT: i64 = 20;
+pub const MAX_LIMIT: i64 = 100;
+
+/// 允许出现的参数名
+const FIELDS: &[&str] = &["title_contains", "published", "id_in", "created_between", "sort", "limit"];
+
+/// 允许排序的列
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortColumn {
+    Id,
+    Title,
+    CreatedAt,
+    UpdatedAt,
+}
+
+impl SortColumn {
+    const ALL: &[(&str, SortColumn)] = &[
+        ("id", SortColumn::Id),
+        ("title", SortColumn::Title),
+        ("created_at", SortColumn::CreatedAt),
+        ("updated_at", SortColumn::UpdatedAt),
+    ];
+
+    fn parse(name: &str) -> Result<Self, AppError> {
+        Self::ALL.iter().find(|(n, _)| *n == name).map(|(_, column)| *column).ok_or_else(|| {
+            let names: Vec<_> = Self::ALL.iter().map(|(n, _)| *n).collect();
+            AppError::Validation(format!("不支持按 `{}` 排序，可选的列: {}", name, names.join(", ")))
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortField {
+    pub column: SortColumn,
+    pub descending: bool,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct PostFilter {
+    /// 标题包含的文字，不区分大小写
+    pub title_contains: Option<String>,
+    pub published: Option<bool>,
+    pub id_in: Option<Vec<i32>>,
+    /// 创建时间范围，包含起点、不包含终点
+    pub created_between: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    /// 为空时按 `id` 升序
+    pub sort: Vec<SortField>,
+    pub limit: Option<i64>,
+}
+
+impl PostFilter {
+    /// 解析 URL 查询字符串，如 `published=true&sort=title:asc`，开头的 `?` 可以省略
+    pub fn from_query_string(query: &str) -> Result<Self, AppError> {
+        let query = query.strip_prefix('?').unwrap_or(query);
+        Self::from_pairs(form_urlencoded::parse(query.as_bytes()))
+    }
+
+    /// 解析命令行参数，如 `--title-contains diesel --sort=title:asc`
+    pub fn from_args<I>(args: I) -> Result<Self, AppError>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut args = args.into_iter();
+        let mut pairs = Vec::new();
+        while let Some(arg) = args.next() {
+            let Some(flag) = arg.strip_prefix("--") else {
+                return Err(AppError::Validation(format!("无法识别的参数 `{}`，参数应以 `--` 开头", arg)));
+            };
+            let (name, value) = match flag.split_once('=') {
+                Some((name, value)) => (name.to_string(), value.to_string()),
+                None => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| AppError::Validation(format!("参数 `--{}` 缺少取值", flag)))?;
+                    (flag.to_string(), value)
+                }
+            };
+            pairs.push((name.replace('-', "_"), value));
+        }
+        Self::from_pairs(pairs)
+    }
+
+    /// 逐个解析参数，名称必须在白名单中且不能重复
+    pub fn from_pairs<I, K, V>(pairs: I) -> Result<Self, AppError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let mut filter = PostFilter::default();
+        let mut seen = Vec::new();
+
+        for (name, value) in pairs {
+            let (name, value) = (name.as_ref(), value.as_ref().trim());
+            if !FIELDS.contains(&name) {
+                return Err(AppError::Validation(format!("未知的参数 `{}`，可用的参数: {}", name, FIELDS.join(", "))));
+            }
+            if seen.contains(&name.to_string()) {
+                return Err(AppError::Validation(format!("参数 `{}` 重复出现", name)));
+            }
+            seen.push(name.to_string());
+            if value.is_empty() {
+                return Err(AppError::Validation(format!("参数 `{}` 的取值不能为空", name)));
+            }
+
+            match name {
+                "title_contains" => filter.title_contains = Some(value.to_string()),
+                "published" => filter.published = Some(parse_bool(name, value)?),
+                "id_in" => filter.id_in = Some(parse_ids(value)?),
+                "created_between" => filter.created_between = Some(parse_range(value)?),
+                "sort" => filter.sort = parse_sort(value)?,
+                "limit" => filter.limit = Some(parse_limit(value)?),
+                _ => unreachable!("FIELDS 中的每个参数都已处理"),
+            }
+        }
+        Ok(filter)
+    }
+
+    // #region into_query
+    /// 转换为 boxed 查询，调用方还可以继续追加条件
+    pub fn into_query<'a>(self) -> posts::BoxedQuery<'a, Pg> {
+        let mut query = posts::table.into_boxed();
+
+        if let Some(text) = self.title_contains {
+            query = query.filter(posts::title.ilike(format!("%{}%", escape_like(&text))));
+        }
+        if let Some(published) = self.published {
+            query = query.filter(posts::published.eq(published));
+        }
+        if let Some(ids) = self.id_in {
+            query = query.filter(posts::id.eq_any(ids));
+        }
+        if let Some((start, end)) = self.created_between {
+            query = query.filter(posts::created_at.ge(start)).filter(posts::created_at.lt(end));
+        }
+
+        if self.sort.is_empty() {
+            query = query.order(posts::id.asc());
+        }
+        for field in self.sort {
+            query = match (field.column, field.descending) {
+                (SortColumn::Id, false) => query.then_order_by(posts::id.asc()),
+                (SortColumn::Id, true) => query.then_order_by(posts::id.desc()),
+                (SortColumn::Title, false) => query.then_order_by(posts::title.asc()),
+                (SortColumn::Title, true) => query.then_order_by(posts::title.desc()),
+                (SortColumn::CreatedAt, false) => query.then_order_by(posts::created_at.asc()),
+                (SortColumn::CreatedAt, true) => query.then_order_by(posts::created_at.desc()),
+                (SortColumn::UpdatedAt, false) => query.then_order_by(posts::updated_at.asc()),
+                (SortColumn::UpdatedAt, true) => query.then_order_by(posts::updated_at.desc()),
+            };
+        }
+
+        query.limit(self.limit.unwrap_or(DEFAULT_LIMIT))
+    }
+    // #endregion into_query
+}
+
+fn parse_bool(name: &str, value: &str) -> Result<bool, AppError> {
+    match value {
+        "true" | "1" => Ok(true),
+        "false" | "0" => Ok(false),
+        _ => Err(AppError::Validation(format!("参数 `{}` 只能是 true 或 false，实际为 `{}`", name, value))),
+    }
+}
+
+/// `1,2,3`
+fn parse_ids(value: &str) -> Result<Vec<i32>, AppError> {
+    value
+        .split(',')
+        .map(|id| {
+            id.trim()
+                .parse()
+                .map_err(|_| AppError::Validation(format!("id_in 中的 `{}` 不是有效的 id", id.trim())))
+        })
+        .collect()
+}
+
+/// `起点,终点`，每一端可以是 RFC 3339 时间或 `YYYY-MM-DD`（当天 UTC 零点）
+fn parse_range(value: &str) -> Result<(DateTime<Utc>, DateTime<Utc>), AppError> {
+    let (start, end) = value
+        .split_once(',')
+        .ok_or_else(|| AppError::Validation("created_between 的格式应为 `起点,终点`".into()))?;
+    let (start, end) = (parse_time(start.trim())?, parse_time(end.trim())?);
+    if start >= end {
+        return Err(AppError::Validation("created_between 的起点必须早于终点".into()));
+    }
+    Ok((start, end))
+}
+
+fn parse_time(value: &str) -> Result<DateTime<Utc>, AppError> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(|date| date.and_hms_opt(0, 0, 0).expect("零点总是有效的时间").and_utc())
+        .map_err(|_| AppError::Validation(format!("无法解析时间 `{}`，应为 RFC 3339 或 YYYY-MM-DD", value)))
+}
+
+/// `title:asc,id:desc`，省略方向时为升序
+fn parse_sort(value: &str) -> Result<Vec<SortField>, AppError> {
+    let mut fields: Vec<SortField> = Vec::new();
+    for item in value.split(',') {
+        let (column, direction) = item.trim().split_once(':').unwrap_or((item.trim(), "asc"));
+        let column = SortColumn::parse(column)?;
+        let descending = match direction {
+            "asc" => false,
+            "desc" => true,
+            _ => return Err(AppError::Validation(format!("排序方向只能是 asc 或 desc，实际为 `{}`", direction))),
+        };
+        if fields.iter().any(|field| field.column == column) {
+            return Err(AppError::Validation(format!("排序列 `{}` 重复出现", item.trim())));
+        }
+        fields.push(SortField { column, descending });
+    }
+    Ok(fields)
+}
+
+fn parse_limit(value: &str) -> Result<i64, AppError> {
+    match value.parse::<i64>() {
+        Ok(limit) if (1..=MAX_LIMIT).contains(&limit) => Ok(limit),
+        _ => Err(AppError::Validation(format!("limit 应为 1 到 {} 之间的整数，实际为 `{}`", MAX_LIMIT, value))),
+    }
+}
+
+/// 转义 LIKE 中的通配符，让用户输入的 `%`、`_` 按字面匹配
+fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
@@ -0,0 +1,73 @@
+//! 文章的统计报表：按发布状态分组计数、正文长度统计
+use crate::schema::posts;
+use common::report::ReportRow;
+use diesel::dsl::{avg, count_star, max};
+use diesel::prelude::*;
+use diesel::sql_types::{Double, Text};
+
+define_sql_function! {
+    /// 字符数（而不是字节数）
+    fn char_length(text: Text) -> Integer;
+}
+
+#[derive(Queryable, Debug)]
+pub struct PostsByState {
+    pub published: bool,
+    pub posts: i64,
+}
+
+#[derive(Queryable, Debug)]
+pub struct BodyLengthStats {
+    pub published: bool,
+    pub posts: i64,
+    /// 分组中没有任何行时为 `None`
+    pub avg_length: Option<f64>,
+    pub max_length: Option<i32>,
+}
+
+// #region group_by
+/// 每种发布状态下的文章数量
+pub fn posts_by_state(conn: &mut PgConnection) -> QueryResult<Vec<PostsByState>> {
+    posts::table
+        .group_by(posts::published)
+        .select((posts::published, count_star()))
+        .order(posts::published.desc())
+        .load(conn)
+}
+
+/// 每种发布状态下正文的平均长度与最大长度，只统计至少有 `min_posts` 篇文章的分组
+pub fn body_length_stats(conn: &mut PgConnection, min_posts: i64) -> QueryResult<Vec<BodyLengthStats>> {
+    let length = char_length(posts::body);
+    posts::table
+        .group_by(posts::published)
+        .having(count_star().ge(min_posts))
+        .select((posts::published, count_star(), avg(length.cast::<Double>()), max(length)))
+        .order(posts::published.desc())
+        .load(conn)
+}
+// #endregion group_by
+
+impl ReportRow for PostsByState {
+    const HEADERS: &'static [&'static str] = &["状态", "文章数"];
+
+    fn cells(&self) -> Vec<String> {
+        vec![state_name(self.published).into(), self.posts.to_string()]
+    }
+}
+
+impl ReportRow for BodyLengthStats {
+    const HEADERS: &'static [&'static str] = &["状态", "文章数", "平均长度", "最大长度"];
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            state_name(self.published).into(),
+            self.posts.to_string(),
+            self.avg_length.map(|avg| format!("{:.1}", avg)).unwrap_or_default(),
+            self.max_length.map(|max| max.to_string()).unwrap_or_default(),
+        ]
+    }
+}
+
+fn state_name(published: bool) -> &'static str {
+    if published { "已发布" } else { "草稿" }
+}
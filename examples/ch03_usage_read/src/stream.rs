@@ -0,0 +1,55 @@
+//! 逐行处理文章，读取任意多的行时内存占用保持不变
+use crate::models::Post;
+use crate::schema::posts;
+use common::AppError;
+use common::cursor::for_each_fetched;
+use diesel::pg::{Pg, PgRowByRowLoadingMode};
+use diesel::prelude::*;
+use std::io::{self, Write};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamMode {
+    /// `load_iter` 逐行加载，每一行都与服务器往返一次
+    RowByRow,
+    /// 服务端游标，每次 `FETCH` 读取 `fetch_size` 行
+    Cursor { fetch_size: i64 },
+}
+
+// #region stream
+/// 按 `mode` 逐行读取 `query` 的结果交给 `f`，返回处理的行数
+pub fn for_each_post<F>(conn: &mut PgConnection, query: posts::BoxedQuery<'_, Pg>, mode: StreamMode, mut f: F) -> Result<usize, AppError>
+where
+    F: FnMut(Post) -> Result<(), AppError>,
+{
+    let query = query.select(Post::as_select());
+    match mode {
+        StreamMode::RowByRow => {
+            let mut count = 0;
+            for post in query.load_iter::<Post, PgRowByRowLoadingMode>(conn)? {
+                f(post?)?;
+                count += 1;
+            }
+            Ok(count)
+        }
+        StreamMode::Cursor { fetch_size } => for_each_fetched(conn, query, fetch_size, f),
+    }
+}
+// #endregion stream
+
+// #region ndjson
+/// 把查询结果逐行写为 NDJSON，每行一个 JSON 对象
+pub fn write_ndjson<W: Write>(
+    conn: &mut PgConnection,
+    query: posts::BoxedQuery<'_, Pg>,
+    mode: StreamMode,
+    out: &mut W,
+) -> Result<usize, AppError> {
+    let count = for_each_post(conn, query, mode, |post| {
+        serde_json::to_writer(&mut *out, &post).map_err(io::Error::from)?;
+        out.write_all(b"\n")?;
+        Ok(())
+    })?;
+    out.flush()?;
+    Ok(count)
+}
+// #endregion ndjson
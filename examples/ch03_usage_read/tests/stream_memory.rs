@@ -0,0 +1,57 @@
+//! 验证流式导出的内存占用不随行数增长
+//!
+//! 在一个最终回滚的事务中写入大量文章，分别用两种流式方式导出为 NDJSON，
+//! 比较导出前后进程的峰值内存（/proc/self/status 中的 VmHWM）。
+//! 需要数据库，未设置 `DATABASE_URL` 时跳过。
+use ch03_usage_read::schema::posts;
+use ch03_usage_read::stream::{self, StreamMode};
+use ch03_usage_read::try_establish_connection;
+use diesel::prelude::*;
+use std::io;
+
+/// 写入的文章数量，每篇正文约 1 KB，全部读入内存约需 50 MB
+const ROWS: i64 = 50_000;
+/// 导出期间允许增长的峰值内存
+const MAX_GROWTH_KB: u64 = 32 * 1024;
+
+#[test]
+fn streaming_export_keeps_memory_bounded() {
+    if common::database_url().is_err() {
+        eprintln!("未设置 DATABASE_URL，跳过");
+        return;
+    }
+    let Some(mut before) = peak_memory_kb() else {
+        eprintln!("无法读取 /proc/self/status，跳过");
+        return;
+    };
+
+    let connection = &mut try_establish_connection().unwrap_or_else(|e| panic!("{}", e));
+    // 测试事务在连接关闭时回滚，不会留下写入的数据
+    connection.begin_test_transaction().unwrap();
+    diesel::sql_query(
+        "INSERT INTO posts (title, body, published) \
+         SELECT 'stream ' || n, repeat('x', 1024), true FROM generate_series(1, $1) AS n",
+    )
+    .bind::<diesel::sql_types::BigInt, _>(ROWS)
+    .execute(connection)
+    .unwrap();
+
+    for mode in [StreamMode::Cursor { fetch_size: 1000 }, StreamMode::RowByRow] {
+        before = peak_memory_kb().unwrap_or(before);
+        let query = posts::table.order(posts::id.asc()).into_boxed();
+        let count = stream::write_ndjson(connection, query, mode, &mut io::sink()).unwrap_or_else(|e| panic!("{}", e));
+        let growth = peak_memory_kb().unwrap_or(before).saturating_sub(before);
+
+        assert!(count as i64 >= ROWS, "{:?}: 只导出了 {} 行", mode, count);
+        assert!(growth <= MAX_GROWTH_KB, "{:?}: 峰值内存增长 {} KB，超过 {} KB", mode, growth, MAX_GROWTH_KB);
+    }
+}
+
+/// 进程启动以来的峰值常驻内存，非 Linux 系统返回 `None`
+fn peak_memory_kb() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))
+        .and_then(|value| value.trim().trim_end_matches("kB").trim().parse().ok())
+}
@@ -0,0 +1,5 @@
+-- Your SQL goes here
+-- 外部系统中的文章标识，作为 upsert 的自然键；手动创建的文章没有外部标识，允许为 NULL
+ALTER TABLE posts
+    ADD COLUMN external_id VARCHAR,
+    ADD CONSTRAINT posts_external_id_key UNIQUE (external_id);
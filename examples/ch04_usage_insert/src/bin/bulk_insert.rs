@@ -0,0 +1,28 @@
+use ch04_usage_insert::bulk::{BulkInsert, TransactionMode};
+use ch04_usage_insert::establish_connection;
+use ch04_usage_insert::models::NewPost;
+use diesel::prelude::*;
+
+fn main() {
+    let conn = &mut establish_connection();
+
+    // 每篇文章 2 个绑定参数，一条语句放不下 10 万篇
+    let total = std::env::args().nth(1).and_then(|n| n.parse().ok()).unwrap_or(100_000);
+    let rows = (1..=total).map(|i| NewPost {
+        title: format!("批量帖子 {}", i),
+        body: format!("批量内容 {}", i),
+    });
+
+    // 示例结束后回滚，不会留下数据
+    conn.begin_test_transaction().unwrap();
+
+    let ids = BulkInsert::new()
+        .mode(TransactionMode::PerChunk) // [!code focus]
+        .on_progress(|progress| println!("第 {} 批完成，已插入 {} 行", progress.chunks, progress.inserted)) // [!code focus]
+        .insert_ids(conn, rows) // [!code focus]
+        .unwrap_or_else(|e| panic!("{}", e));
+
+    if let (Some(first), Some(last)) = (ids.first(), ids.last()) {
+        println!("共插入 {} 篇文章，id 从 {} 到 {}", ids.len(), first, last);
+    }
+}
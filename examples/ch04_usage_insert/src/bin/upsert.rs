@@ -0,0 +1,41 @@
+use ch04_usage_insert::establish_connection;
+use ch04_usage_insert::upsert::{upsert_posts, OnConflict, UpsertColumn, UpsertPost, UpsertResult};
+use ch04_usage_insert::AppError;
+use chrono::{Duration, Utc};
+
+fn main() {
+    let conn = &mut establish_connection();
+
+    let now = Utc::now();
+    let post = |external_id: &str, title: &str, minutes_ago: i64| UpsertPost {
+        external_id: external_id.into(),
+        title: title.into(),
+        body: format!("{} 的内容", title),
+        published: true,
+        updated_at: now - Duration::minutes(minutes_ago),
+    };
+
+    // 第一次同步：全部插入（再次运行时则全部更新）
+    let first = vec![post("cms-1", "Rust 入门", 10), post("cms-2", "Diesel 入门", 10)];
+    report("首次同步", upsert_posts(conn, &first, &OnConflict::UpdateAll));
+
+    // 已存在的文章保持不变，新文章插入
+    let second = vec![post("cms-2", "Diesel 入门（修订）", 5), post("cms-3", "连接池", 5)];
+    report("DO NOTHING", upsert_posts(conn, &second, &OnConflict::DoNothing));
+
+    // 只覆盖标题
+    let titles = OnConflict::UpdateColumns(vec![UpsertColumn::Title]);
+    report("只更新标题", upsert_posts(conn, &second, &titles));
+
+    // cms-1 的版本比数据库中的旧，不会覆盖；cms-2 更新
+    let third = vec![post("cms-1", "Rust 入门（旧版本）", 60), post("cms-2", "Diesel 入门（第三版）", 1)];
+    report("只接受更新的版本", upsert_posts(conn, &third, &OnConflict::UpdateIfNewer)); // [!code focus]
+}
+
+fn report(label: &str, results: Result<Vec<UpsertResult>, AppError>) {
+    println!("{}:", label);
+    for result in results.unwrap_or_else(|e| panic!("{}", e)) {
+        let id = result.id.map(|id| id.to_string()).unwrap_or_else(|| "-".into());
+        println!("  {:<6} id={:<4} {:?}", result.external_id, id, result.outcome);
+    }
+}
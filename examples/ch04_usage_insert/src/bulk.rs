@@ -0,0 +1,193 @@
+//! 分批插入大量文章
+//!
+//! Postgres 一条语句最多只能有 65535 个绑定参数，`insert_into(posts).values(&new_posts)` 中每篇文章的每一列
+//! 都是一个绑定参数，行数一多就会失败。`BulkInsert` 根据每行的列数自动切分，从迭代器中逐批读取，
+//! 不需要事先把所有行收集到内存中。
+//!
+//! ```ignore
+//! let ids = BulkInsert::new()
+//!     .mode(TransactionMode::PerChunk)
+//!     .on_progress(|progress| println!("已插入 {} 行", progress.inserted))
+//!     .insert_ids(conn, rows)?;
+//! ```
+use crate::models::{NewPost, Post};
+use crate::schema::posts;
+use common::AppError;
+use diesel::debug_query;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use std::error::Error;
+use std::fmt;
+
+/// Postgres 单条语句允许的最多绑定参数数量
+pub const MAX_BIND_PARAMS: usize = 65535;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionMode {
+    /// 所有批次在同一个事务中，任何一批失败都不会留下数据
+    All,
+    /// 每批一个事务，失败时之前的批次已经提交
+    PerChunk,
+}
+
+/// 每插入完一批后报告的进度
+#[derive(Debug, Clone, Copy)]
+pub struct Progress {
+    /// 已完成的批次数
+    pub chunks: usize,
+    /// 已插入的总行数
+    pub inserted: usize,
+}
+
+/// 批量插入失败，同时带回失败之前已经提交的部分
+///
+/// `TransactionMode::PerChunk` 下失败之前的批次已经提交，`committed` 为这些批次的结果；
+/// `TransactionMode::All` 下整个事务已回滚，`committed` 为空。
+#[derive(Debug)]
+pub struct BulkInsertError<T> {
+    pub error: AppError,
+    /// 已提交的进度
+    pub progress: Progress,
+    /// 已提交的行，按插入顺序排列
+    pub committed: Vec<T>,
+}
+
+impl<T> fmt::Display for BulkInsertError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}（已提交 {} 批，共 {} 行）", self.error, self.progress.chunks, self.progress.inserted)
+    }
+}
+
+impl<T: fmt::Debug> Error for BulkInsertError<T> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl<T> From<BulkInsertError<T>> for AppError {
+    fn from(err: BulkInsertError<T>) -> Self {
+        err.error
+    }
+}
+
+pub struct BulkInsert<'f> {
+    mode: TransactionMode,
+    max_chunk_rows: Option<usize>,
+    on_progress: Option<Box<dyn FnMut(Progress) + 'f>>,
+}
+
+impl Default for BulkInsert<'_> {
+    fn default() -> Self {
+        BulkInsert { mode: TransactionMode::All, max_chunk_rows: None, on_progress: None }
+    }
+}
+
+impl<'f> BulkInsert<'f> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn mode(self, mode: TransactionMode) -> Self {
+        BulkInsert { mode, ..self }
+    }
+
+    /// 每批最多的行数，默认只受绑定参数数量限制
+    pub fn max_chunk_rows(self, rows: usize) -> Self {
+        BulkInsert { max_chunk_rows: Some(rows.max(1)), ..self }
+    }
+
+    pub fn on_progress(self, f: impl FnMut(Progress) + 'f) -> Self {
+        BulkInsert { on_progress: Some(Box::new(f)), ..self }
+    }
+
+    /// 插入所有行，按插入顺序返回新文章的 id
+    pub fn insert_ids<I>(self, conn: &mut PgConnection, rows: I) -> Result<Vec<i32>, BulkInsertError<i32>>
+    where
+        I: IntoIterator<Item = NewPost>,
+    {
+        self.run(conn, rows, |conn, chunk| {
+            diesel::insert_into(posts::table).values(chunk).returning(posts::id).get_results(conn)
+        })
+    }
+
+    /// 插入所有行，按插入顺序返回新文章
+    pub fn insert_rows<I>(self, conn: &mut PgConnection, rows: I) -> Result<Vec<Post>, BulkInsertError<Post>>
+    where
+        I: IntoIterator<Item = NewPost>,
+    {
+        self.run(conn, rows, |conn, chunk| {
+            diesel::insert_into(posts::table).values(chunk).returning(Post::as_returning()).get_results(conn)
+        })
+    }
+
+    // #region run
+    fn run<I, T, F>(mut self, conn: &mut PgConnection, rows: I, mut insert: F) -> Result<Vec<T>, BulkInsertError<T>>
+    where
+        I: IntoIterator<Item = NewPost>,
+        F: FnMut(&mut PgConnection, &[NewPost]) -> QueryResult<Vec<T>>,
+    {
+        let mut rows = rows.into_iter().peekable();
+        let Some(first) = rows.peek() else {
+            return Ok(Vec::new());
+        };
+
+        let chunk_rows = (MAX_BIND_PARAMS / columns_per_row(first)).min(self.max_chunk_rows.unwrap_or(usize::MAX));
+        let mode = self.mode;
+
+        let mut inserted = Vec::new();
+        let mut progress = Progress { chunks: 0, inserted: 0 };
+        let mut insert_all = |conn: &mut PgConnection| -> Result<(), AppError> {
+            let mut chunk = Vec::with_capacity(chunk_rows);
+            loop {
+                chunk.extend(rows.by_ref().take(chunk_rows));
+                if chunk.is_empty() {
+                    break;
+                }
+
+                let results = match mode {
+                    TransactionMode::All => insert(conn, &chunk)?,
+                    TransactionMode::PerChunk => conn.transaction(|conn| insert(conn, &chunk))?,
+                };
+                progress.chunks += 1;
+                progress.inserted += results.len();
+                inserted.extend(results);
+                chunk.clear();
+
+                if let Some(on_progress) = self.on_progress.as_mut() {
+                    on_progress(progress);
+                }
+            }
+            Ok(())
+        };
+
+        let result = match mode {
+            TransactionMode::All => conn.transaction(|conn| insert_all(conn)),
+            TransactionMode::PerChunk => insert_all(conn),
+        };
+        match result {
+            Ok(()) => Ok(inserted),
+            Err(error) => {
+                // 整个事务已回滚，之前插入的批次都不存在了
+                if mode == TransactionMode::All {
+                    inserted.clear();
+                    progress = Progress { chunks: 0, inserted: 0 };
+                }
+                Err(BulkInsertError { error, progress, committed: inserted })
+            }
+        }
+    }
+    // #endregion run
+}
+
+/// 每行占用的绑定参数数量上限，即 INSERT 语句中列的数量
+///
+/// 批量插入时 `None` 的字段会写成 `DEFAULT` 而不是绑定参数，因此按列数计算是安全的上限。
+/// 列数从只有一行的 INSERT 语句中得出，`NewPost` 增加字段后不需要修改这里。
+fn columns_per_row(row: &NewPost) -> usize {
+    let insert = diesel::insert_into(posts::table).values(std::slice::from_ref(row));
+    let sql = debug_query::<Pg, _>(&insert).to_string();
+    // INSERT INTO "posts" ("title", "body") VALUES ($1, $2) -- binds: [...]
+    let columns = sql.split_once(" VALUES ").map_or("", |(columns, _)| columns);
+    let columns = columns.split_once('(').map_or("", |(_, columns)| columns);
+    (columns.matches('"').count() / 2).max(1)
+}
@@ -1,20 +1,22 @@
 use diesel::prelude::*;
-use std::env;
-use dotenvy::dotenv;
+use diesel_migrations::{embed_migrations, EmbeddedMigrations};
 pub mod schema;
+pub mod bulk;
 pub mod models;
+pub mod upsert;
 
-pub fn establish_connection() -> PgConnection {
-    // 让我们可以获取环境变量 .env 内容
-    dotenv().ok();
-
-    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+pub use common::AppError;
 
-    PgConnection::establish(&database_url)
-    .unwrap_or_else(|_| panic!("Error connecting to {}", database_url))
-}
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
 
+/// 本示例在数据库中使用的 schema，与其他章节的表和迁移记录互不影响
+pub const SCHEMA: &str = env!("CARGO_PKG_NAME");
 
-fn main() {
+pub fn establish_connection() -> PgConnection {
+    try_establish_connection().unwrap_or_else(|e| panic!("{}", e))
+}
 
-}
\ No newline at end of file
+/// 建立连接，并在启动时自动执行尚未执行的内嵌迁移
+pub fn try_establish_connection() -> Result<PgConnection, AppError> {
+    common::try_establish_migrated_connection(SCHEMA, &MIGRATIONS)
+}
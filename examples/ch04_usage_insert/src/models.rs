@@ -1,5 +1,6 @@
+use chrono::{DateTime, Utc};
 use diesel::prelude::*;
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 
 #[derive(Queryable, Selectable, Serialize)]
 #[diesel(table_name = crate::schema::posts)]
@@ -9,6 +10,10 @@ pub struct Post {
     pub title: String,
     pub body: String,
     pub published: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// 外部系统中的标识，upsert 时作为自然键
+    pub external_id: Option<String>,
 }
 
 #[derive(Insertable)]
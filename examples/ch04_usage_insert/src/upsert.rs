@@ -0,0 +1,160 @@
+//! 以 `external_id` 为自然键插入或更新文章（`INSERT ... ON CONFLICT`）
+use crate::schema::posts;
+use chrono::{DateTime, Utc};
+use common::AppError;
+use diesel::dsl::sql;
+use diesel::prelude::*;
+use diesel::query_dsl::methods::FilterDsl;
+use diesel::sql_types::Bool;
+use diesel::upsert::excluded;
+use std::collections::{HashMap, HashSet};
+
+/// 单条 INSERT 语句包含的最大记录数，避免超出绑定参数数量限制
+const UPSERT_CHUNK_SIZE: usize = 1000;
+
+/// 来自外部系统的文章
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = posts)]
+pub struct UpsertPost {
+    pub external_id: String,
+    pub title: String,
+    pub body: String,
+    pub published: bool,
+    /// 外部系统中最后修改的时间，`UpdateIfNewer` 据此判断是否覆盖
+    pub updated_at: DateTime<Utc>,
+}
+
+/// `do_update` 时可以覆盖的列
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpsertColumn {
+    Title,
+    Body,
+    Published,
+}
+
+/// `external_id` 已存在时的处理方式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OnConflict {
+    /// 保留已有的文章：`DO NOTHING`
+    DoNothing,
+    /// 覆盖所有列：`DO UPDATE SET title = excluded.title, ...`
+    UpdateAll,
+    /// 只覆盖指定的列
+    UpdateColumns(Vec<UpsertColumn>),
+    /// 只有导入的版本更新时才覆盖所有列：`DO UPDATE ... WHERE excluded.updated_at > posts.updated_at`
+    UpdateIfNewer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpsertOutcome {
+    Inserted,
+    Updated,
+    /// 已存在且没有被更新（`DO NOTHING`，或者不满足 `UpdateIfNewer` 的条件）
+    Skipped,
+}
+
+#[derive(Debug)]
+pub struct UpsertResult {
+    pub external_id: String,
+    /// 跳过的文章没有被 `RETURNING` 返回，此时为 `None`
+    pub id: Option<i32>,
+    pub outcome: UpsertOutcome,
+}
+
+// #region upsert
+/// 按 `on_conflict` 插入或更新文章，按输入顺序返回每篇文章的结果
+///
+/// `RETURNING (xmax = 0)`：新插入的行版本没有 `xmax`，被 `ON CONFLICT DO UPDATE` 更新的行版本
+/// 的 `xmax` 为当前事务 id，因此可以据此区分插入与更新。被跳过的行不会出现在 `RETURNING` 中。
+pub fn upsert_posts(
+    conn: &mut PgConnection,
+    records: &[UpsertPost],
+    on_conflict: &OnConflict,
+) -> Result<Vec<UpsertResult>, AppError> {
+    validate(records, on_conflict)?;
+
+    let returned: HashMap<String, (i32, bool)> = conn.transaction(|conn| {
+        let mut returned = HashMap::new();
+        for chunk in records.chunks(UPSERT_CHUNK_SIZE) {
+            let rows = upsert_chunk(conn, chunk, on_conflict)?;
+            returned.extend(rows.into_iter().map(|(id, external_id, inserted)| (external_id, (id, inserted))));
+        }
+        Ok::<_, AppError>(returned)
+    })?;
+
+    Ok(records
+        .iter()
+        .map(|record| {
+            let (id, outcome) = match returned.get(&record.external_id) {
+                Some((id, true)) => (Some(*id), UpsertOutcome::Inserted),
+                Some((id, false)) => (Some(*id), UpsertOutcome::Updated),
+                None => (None, UpsertOutcome::Skipped),
+            };
+            UpsertResult { external_id: record.external_id.clone(), id, outcome }
+        })
+        .collect())
+}
+
+fn upsert_chunk(
+    conn: &mut PgConnection,
+    chunk: &[UpsertPost],
+    on_conflict: &OnConflict,
+) -> QueryResult<Vec<(i32, String, bool)>> {
+    let insert = diesel::insert_into(posts::table).values(chunk);
+    // external_id 可以为 NULL，但 upsert 的记录一定有值，因此返回 String
+    let returning = (posts::id, posts::external_id.assume_not_null(), sql::<Bool>("(xmax = 0)"));
+    let update_all = (
+        posts::title.eq(excluded(posts::title)),
+        posts::body.eq(excluded(posts::body)),
+        posts::published.eq(excluded(posts::published)),
+        posts::updated_at.eq(excluded(posts::updated_at)),
+    );
+
+    match on_conflict {
+        OnConflict::DoNothing => {
+            insert.on_conflict(posts::external_id).do_nothing().returning(returning).load(conn)
+        }
+        OnConflict::UpdateAll => {
+            insert.on_conflict(posts::external_id).do_update().set(update_all).returning(returning).load(conn)
+        }
+        OnConflict::UpdateColumns(columns) => {
+            // 值为 None 的 Option 不会出现在 SET 中；updated_at 总是与外部系统保持一致
+            let selected = |column: UpsertColumn| columns.contains(&column);
+            let changes = (
+                selected(UpsertColumn::Title).then(|| posts::title.eq(excluded(posts::title))),
+                selected(UpsertColumn::Body).then(|| posts::body.eq(excluded(posts::body))),
+                selected(UpsertColumn::Published).then(|| posts::published.eq(excluded(posts::published))),
+                posts::updated_at.eq(excluded(posts::updated_at)),
+            );
+            insert.on_conflict(posts::external_id).do_update().set(changes).returning(returning).load(conn)
+        }
+        OnConflict::UpdateIfNewer => insert
+            .on_conflict(posts::external_id)
+            .do_update()
+            .set(update_all)
+            .filter(excluded(posts::updated_at).gt(posts::updated_at))
+            .returning(returning)
+            .load(conn),
+    }
+}
+// #endregion upsert
+
+/// 同一条语句中同一个键出现两次时，Postgres 会报 "cannot affect row a second time"，提前给出明确的错误
+fn validate(records: &[UpsertPost], on_conflict: &OnConflict) -> Result<(), AppError> {
+    if let OnConflict::UpdateColumns(columns) = on_conflict
+        && columns.is_empty()
+    {
+        return Err(AppError::Validation("至少需要指定一个要更新的列".into()));
+    }
+
+    let mut seen = HashSet::new();
+    for record in records {
+        if record.external_id.trim().is_empty() {
+            return Err(AppError::Validation("external_id 不能为空".into()));
+        }
+        if !seen.insert(record.external_id.as_str()) {
+            return Err(AppError::Validation(format!("external_id 重复: {}", record.external_id)));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER IF EXISTS set_updated_at ON posts;
+
+ALTER TABLE posts
+    DROP COLUMN created_at,
+    DROP COLUMN updated_at;
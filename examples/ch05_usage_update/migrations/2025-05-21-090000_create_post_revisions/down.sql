@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER IF EXISTS record_post_revision_on_update ON posts;
+DROP TRIGGER IF EXISTS record_post_revision_on_insert ON posts;
+DROP FUNCTION IF EXISTS record_post_revision();
+DROP TABLE post_revisions;
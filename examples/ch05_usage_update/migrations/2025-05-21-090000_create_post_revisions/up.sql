@@ -0,0 +1,37 @@
+-- Your SQL goes here
+CREATE TABLE post_revisions (
+    id SERIAL PRIMARY KEY,
+    post_id INTEGER NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    revision INTEGER NOT NULL,
+    title VARCHAR NOT NULL,
+    body TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (post_id, revision)
+);
+
+-- 为已有文章补充第一条修订
+INSERT INTO post_revisions (post_id, revision, title, body, created_at)
+SELECT id, 1, title, body, updated_at FROM posts;
+
+-- 记录文章当前的标题与内容，修订号在每篇文章内递增
+-- 修改文章时 posts 行已被锁定，因此同一篇文章的修订号不会冲突
+CREATE OR REPLACE FUNCTION record_post_revision() RETURNS trigger AS $$
+BEGIN
+    INSERT INTO post_revisions (post_id, revision, title, body)
+    SELECT NEW.id, COALESCE(MAX(revision), 0) + 1, NEW.title, NEW.body
+    FROM post_revisions
+    WHERE post_id = NEW.id;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+-- 触发器与修改文章的语句在同一个事务中执行，语句回滚时修订记录也会一起回滚
+CREATE TRIGGER record_post_revision_on_insert
+    AFTER INSERT ON posts
+    FOR EACH ROW EXECUTE PROCEDURE record_post_revision();
+
+CREATE TRIGGER record_post_revision_on_update
+    AFTER UPDATE OF title, body ON posts
+    FOR EACH ROW
+    WHEN (OLD.title IS DISTINCT FROM NEW.title OR OLD.body IS DISTINCT FROM NEW.body)
+    EXECUTE PROCEDURE record_post_revision();
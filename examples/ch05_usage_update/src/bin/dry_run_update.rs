@@ -0,0 +1,23 @@
+use ch05_usage_update::{establish_connection, models::Post, schema};
+use common::dry_run::dry_run;
+use diesel::prelude::*;
+
+fn main() -> Result<(), common::AppError> {
+    use schema::posts::dsl::*;
+
+    let conn = &mut establish_connection();
+
+    // 与 update.rs 相同的语句，加上 RETURNING 以便查看受影响的行
+    let statement = diesel::update(posts) // [!code focus:4]
+        .set(published.eq(true))
+        .returning(Post::as_returning());
+    let preview = dry_run(conn, statement, 3)?;
+
+    println!("SQL: {}", preview.sql);
+    println!("将更新 {} 篇文章，前 {} 篇：", preview.affected, preview.sample.len());
+    for post in preview.sample {
+        println!("{} {}", post.id, post.title);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use ch05_usage_update::{establish_connection, models::UpdatePost, revisions, schema};
+use diesel::prelude::*;
+
+fn main() -> Result<(), diesel::result::Error> {
+    use schema::posts::dsl::*;
+
+    let conn = &mut establish_connection();
+    let post_id = 1;
+
+    // 修改文章时，触发器会在同一事务中写入一条修订
+    let new_post = UpdatePost {
+        title: "Rust文章".into(),
+        body: "Rust内容（修订版）".into(),
+    };
+    diesel::update(posts.find(post_id))
+        .set(&new_post)
+        .execute(conn)?;
+
+    let history = revisions::list_revisions(conn, post_id)?; // [!code focus:3]
+    for revision in &history {
+        println!("#{} {} {}", revision.revision, revision.created_at, revision.title);
+    }
+
+    // history 按修订号从新到旧排列
+    if let [latest, .., oldest] = history.as_slice() {
+        let diff = revisions::diff_revisions(conn, post_id, oldest.revision, latest.revision)?; // [!code focus]
+        println!("{}", diff);
+
+        let post = revisions::restore_revision(conn, post_id, oldest.revision)?; // [!code focus]
+        println!("已恢复到修订 #{}：{}", oldest.revision, post.title);
+    }
+
+    Ok(())
+}
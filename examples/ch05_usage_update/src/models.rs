@@ -1,7 +1,8 @@
+use chrono::{DateTime, Utc};
 use diesel::prelude::*;
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 
-#[derive(Queryable, Selectable, Serialize)]
+#[derive(Queryable, Selectable, Identifiable, Serialize)]
 #[diesel(table_name = crate::schema::posts)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct Post {
@@ -9,6 +10,8 @@ pub struct Post {
     pub title: String,
     pub body: String,
     pub published: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
 }
 
 #[derive(Insertable)]
@@ -23,4 +26,17 @@ pub struct NewPost {
 pub struct UpdatePost {
     pub title: String,
     pub body: String,
-}
\ No newline at end of file
+}
+/// 文章的一次修订，由数据库触发器在文章插入或修改标题、内容时写入
+#[derive(Queryable, Selectable, Identifiable, Associations, Serialize, Debug)]
+#[diesel(belongs_to(Post))]
+#[diesel(table_name = crate::schema::post_revisions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct PostRevision {
+    pub id: i32,
+    pub post_id: i32,
+    pub revision: i32,
+    pub title: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
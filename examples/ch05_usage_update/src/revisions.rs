@@ -0,0 +1,54 @@
+use crate::models::{Post, PostRevision};
+use crate::schema::{post_revisions, posts};
+use diesel::prelude::*;
+use similar::TextDiff;
+
+/// 按修订号从新到旧列出文章的全部修订
+pub fn list_revisions(conn: &mut PgConnection, post_id: i32) -> QueryResult<Vec<PostRevision>> {
+    post_revisions::table
+        .filter(post_revisions::post_id.eq(post_id))
+        .order(post_revisions::revision.desc())
+        .select(PostRevision::as_select())
+        .load(conn)
+}
+
+pub fn find_revision(conn: &mut PgConnection, post_id: i32, revision: i32) -> QueryResult<PostRevision> {
+    post_revisions::table
+        .filter(post_revisions::post_id.eq(post_id))
+        .filter(post_revisions::revision.eq(revision))
+        .select(PostRevision::as_select())
+        .first(conn)
+}
+
+/// 生成两个修订之间的 unified diff，标题作为第一行参与比较
+pub fn diff_revisions(conn: &mut PgConnection, post_id: i32, from: i32, to: i32) -> QueryResult<String> {
+    let old = find_revision(conn, post_id, from)?;
+    let new = find_revision(conn, post_id, to)?;
+
+    let old_text = revision_text(&old);
+    let new_text = revision_text(&new);
+    let diff = TextDiff::from_lines(&old_text, &new_text)
+        .unified_diff()
+        .header(&format!("revision {}", from), &format!("revision {}", to))
+        .to_string();
+
+    Ok(diff)
+}
+
+/// 将文章恢复为旧修订的内容
+///
+/// 恢复本身也是一次修改，触发器会把它记录为一条新的修订，原有历史不会被改写。
+pub fn restore_revision(conn: &mut PgConnection, post_id: i32, revision: i32) -> QueryResult<Post> {
+    conn.transaction(|conn| {
+        let old = find_revision(conn, post_id, revision)?;
+
+        diesel::update(posts::table.find(post_id))
+            .set((posts::title.eq(old.title), posts::body.eq(old.body)))
+            .returning(Post::as_returning())
+            .get_result(conn)
+    })
+}
+
+fn revision_text(revision: &PostRevision) -> String {
+    format!("{}\n\n{}\n", revision.title, revision.body)
+}
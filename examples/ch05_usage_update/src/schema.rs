@@ -1,10 +1,30 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    post_revisions (id) {
+        id -> Int4,
+        post_id -> Int4,
+        revision -> Int4,
+        title -> Varchar,
+        body -> Text,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     posts (id) {
         id -> Int4,
         title -> Varchar,
         body -> Text,
         published -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
     }
 }
+
+diesel::joinable!(post_revisions -> posts (post_id));
+
+diesel::allow_tables_to_appear_in_same_query!(
+    post_revisions,
+    posts,
+);
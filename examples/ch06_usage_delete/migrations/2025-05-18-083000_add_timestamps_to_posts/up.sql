@@ -0,0 +1,7 @@
+-- Your SQL goes here
+ALTER TABLE posts
+    ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+-- 使用初始迁移中的辅助函数，在更新文章时自动刷新 updated_at
+SELECT diesel_manage_updated_at('posts');
@@ -0,0 +1,5 @@
+-- Your SQL goes here
+ALTER TABLE posts ADD COLUMN deleted_at TIMESTAMPTZ;
+
+-- 清理任务只扫描已软删除的文章
+CREATE INDEX posts_deleted_at_idx ON posts (deleted_at) WHERE deleted_at IS NOT NULL;
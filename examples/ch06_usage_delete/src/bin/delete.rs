@@ -1,12 +1,14 @@
-use ch06_usage_delete::{establish_connection, schema};
+use ch06_usage_delete::{establish_connection, soft_delete};
 use diesel::prelude::*;
 
 fn main() {
-    use schema::posts::dsl::*;
+    use soft_delete::{live_posts, mark_deleted};
 
     let conn = &mut establish_connection();
 
-    diesel::delete(posts)
+    // posts 表使用软删除：把所有未删除的文章标记为已删除
+    diesel::update(live_posts())
+        .set(mark_deleted())
         .execute(conn)
         .expect("Error deleting posts");
 }
@@ -1,12 +1,14 @@
-use ch06_usage_delete::{establish_connection, schema};
+use ch06_usage_delete::{establish_connection, schema, soft_delete};
 use diesel::prelude::*;
 
 fn main() {
     use schema::posts::dsl::*;
+    use soft_delete::{live_posts, mark_deleted};
 
     let conn = &mut establish_connection();
 
-    diesel::delete(posts.filter(id.eq(1)))
+    diesel::update(live_posts().filter(id.eq(1)))
+        .set(mark_deleted())
         .execute(conn)
         .expect("Error deleting posts");
 }
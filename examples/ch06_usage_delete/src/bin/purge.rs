@@ -0,0 +1,27 @@
+use ch06_usage_delete::{soft_delete, try_establish_connection, AppError};
+use chrono::{TimeDelta, Utc};
+use clap::Parser;
+use common::parse_age;
+
+/// 彻底删除软删除时间早于指定时长的文章
+#[derive(Parser)]
+#[command(name = "purge")]
+struct Args {
+    /// 软删除超过该时长的文章会被彻底删除，如 30d
+    #[arg(long, value_name = "AGE", value_parser = parse_age)]
+    older_than: TimeDelta,
+    /// 每批删除的最大行数
+    #[arg(long, default_value_t = 500, value_parser = clap::value_parser!(i64).range(1..))]
+    batch_size: i64,
+}
+
+fn main() -> Result<(), AppError> {
+    let args = Args::parse();
+    let conn = &mut try_establish_connection()?;
+
+    let deleted_before = Utc::now() - args.older_than;
+    let purged = soft_delete::purge(conn, deleted_before, args.batch_size)?;
+
+    println!("彻底删除 {} 篇文章", purged);
+    Ok(())
+}
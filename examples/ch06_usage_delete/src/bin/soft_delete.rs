@@ -0,0 +1,23 @@
+use ch06_usage_delete::{establish_connection, models::Post, soft_delete};
+use diesel::prelude::*;
+
+fn main() -> Result<(), diesel::result::Error> {
+    use soft_delete::live_posts;
+
+    let conn = &mut establish_connection();
+
+    // 软删除只写入 deleted_at，数据仍然保留在表中
+    if let Some(post) = soft_delete::soft_delete(conn, 1)? { // [!code focus]
+        println!("已删除：{}", post.title);
+    }
+
+    // 普通查询从 live_posts() 开始，自动排除已删除的文章
+    let visible = live_posts().select(Post::as_select()).load(conn)?; // [!code focus]
+    println!("剩余 {} 篇文章", visible.len());
+
+    if let Some(post) = soft_delete::restore(conn, 1)? { // [!code focus]
+        println!("已恢复：{}", post.title);
+    }
+
+    Ok(())
+}
@@ -1,14 +1,17 @@
-use ch06_usage_delete::{establish_connection, models::Post, schema};
+use ch06_usage_delete::{establish_connection, models::Post, schema, soft_delete};
 use diesel::prelude::*;
 
-fn main() {
+fn main() -> Result<(), diesel::result::Error> {
     use schema::posts::dsl::*;
+    use soft_delete::{live_posts, mark_deleted};
 
     let conn = &mut establish_connection();
 
-    let post = posts.filter(id.eq(1)).first::<Post>(conn)?;
+    let post = live_posts().filter(id.eq(1)).select(Post::as_select()).first(conn)?;
 
-    diesel::delete(&post)
-        .execute(conn)
-        .expect("Error deleting posts");
+    diesel::update(&post)
+        .set(mark_deleted())
+        .execute(conn)?;
+
+    Ok(())
 }
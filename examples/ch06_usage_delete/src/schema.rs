@@ -6,5 +6,8 @@ diesel::table! {
         title -> Varchar,
         body -> Text,
         published -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        deleted_at -> Nullable<Timestamptz>,
     }
 }
@@ -0,0 +1,78 @@
+use crate::models::Post;
+use crate::schema::posts;
+use chrono::{DateTime, Utc};
+use common::AppError;
+use diesel::dsl;
+use diesel::prelude::*;
+
+/// 未被软删除的文章，普通查询都应从这里开始，而不是直接使用 `posts::table`
+#[dsl::auto_type]
+pub fn live_posts() -> _ {
+    posts::table.filter(posts::deleted_at.is_null())
+}
+
+/// 已被软删除、等待恢复或清理的文章
+#[dsl::auto_type]
+pub fn trashed_posts() -> _ {
+    posts::table.filter(posts::deleted_at.is_not_null())
+}
+
+// #region soft_delete
+/// 软删除时写入的变更：`deleted_at = 当前时间`，配合 `diesel::update(live_posts()...)` 使用
+pub fn mark_deleted() -> dsl::Eq<posts::deleted_at, DateTime<Utc>> {
+    posts::deleted_at.eq(Utc::now())
+}
+
+/// 软删除文章：只记录删除时间，文章不存在或已被删除时返回 `None`
+pub fn soft_delete(conn: &mut PgConnection, post_id: i32) -> QueryResult<Option<Post>> {
+    diesel::update(live_posts().filter(posts::id.eq(post_id)))
+        .set(mark_deleted())
+        .returning(Post::as_returning())
+        .get_result(conn)
+        .optional()
+}
+
+/// 恢复被软删除的文章，文章不存在或未被删除时返回 `None`
+pub fn restore(conn: &mut PgConnection, post_id: i32) -> QueryResult<Option<Post>> {
+    diesel::update(trashed_posts().filter(posts::id.eq(post_id)))
+        .set(posts::deleted_at.eq(None::<DateTime<Utc>>))
+        .returning(Post::as_returning())
+        .get_result(conn)
+        .optional()
+}
+// #endregion soft_delete
+
+// #region purge
+/// 彻底删除在 `deleted_before` 之前被软删除的文章，返回删除的总数
+///
+/// 每批最多删除 `batch_size` 行并单独提交，避免一次性长时间锁住大量行；
+/// `SKIP LOCKED` 让多个清理任务可以同时运行。
+pub fn purge(conn: &mut PgConnection, deleted_before: DateTime<Utc>, batch_size: i64) -> Result<usize, AppError> {
+    // LIMIT 0 永远删不到行，循环无法结束
+    if batch_size < 1 {
+        return Err(AppError::Validation(format!("batch_size 必须大于 0，实际为 {}", batch_size)));
+    }
+
+    let mut total = 0;
+
+    loop {
+        let deleted = conn.transaction(|conn| {
+            let ids = trashed_posts()
+                .filter(posts::deleted_at.lt(deleted_before))
+                .order(posts::deleted_at.asc())
+                .limit(batch_size)
+                .select(posts::id)
+                .for_update()
+                .skip_locked()
+                .load::<i32>(conn)?;
+
+            diesel::delete(posts::table.filter(posts::id.eq_any(ids))).execute(conn)
+        })?;
+
+        total += deleted;
+        if (deleted as i64) < batch_size {
+            return Ok(total);
+        }
+    }
+}
+// #endregion purge
@@ -1,5 +1,7 @@
 use ch07_features_transaction::{establish_connection};
 
+// 示例只通过 Debug 输出错误，字段不会被读取
+#[allow(dead_code)]
 #[derive(Debug)]
 enum MyError {
     DieselError(diesel::result::Error),
@@ -12,6 +14,8 @@ impl From<diesel::result::Error> for MyError {
     }
 }
 
+// `if 1 == 1` 模拟业务校验失败，触发回滚
+#[allow(clippy::eq_op, unused_variables)]
 fn main() {
     use diesel::prelude::*;
     let connection = &mut establish_connection();
@@ -6,5 +6,7 @@ diesel::table! {
         title -> Varchar,
         body -> Text,
         published -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
     }
 }
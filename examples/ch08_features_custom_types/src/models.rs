@@ -1,5 +1,4 @@
 use diesel::prelude::*;
-use serde::{Deserialize};
 use crate::custom_email_type::Email;
 
 #[derive(Queryable, Insertable)]
@@ -7,4 +6,4 @@ use crate::custom_email_type::Email;
 pub struct User {
     pub id: i32,
     pub email: Email,
-}
\ No newline at end of file
+}
@@ -0,0 +1,25 @@
+use ch09_features_relations::{pool::establish_connection, report};
+use common::report::{write_report, ReportFormat};
+use std::io;
+
+/// 用法：`book_report [--csv] [最少书籍数]`
+fn main() {
+    let mut format = ReportFormat::Table;
+    let mut min_books = 1;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--csv" => format = ReportFormat::Csv,
+            n => min_books = n.parse().expect("最少书籍数应为整数"),
+        }
+    }
+
+    let conn = &mut establish_connection();
+    let out = &mut io::stdout().lock();
+
+    let pages = report::pages_per_book(conn).expect("Error loading report"); // [!code focus:2]
+    let authors = report::books_per_author(conn, min_books).expect("Error loading report");
+
+    write_report(out, &pages, format).unwrap_or_else(|e| panic!("{}", e));
+    println!();
+    write_report(out, &authors, format).unwrap_or_else(|e| panic!("{}", e));
+}
@@ -0,0 +1,19 @@
+use ch09_features_relations::{models, pool::establish_connection, schema};
+use common::Explain;
+use diesel::prelude::*;
+use models::{Book, Page};
+
+fn main() -> Result<(), common::AppError> {
+    use schema::{books, pages};
+
+    let conn = &mut establish_connection();
+
+    let plan = pages::table // [!code focus:5]
+        .inner_join(books::table)
+        .filter(books::title.eq("Rust"))
+        .select((Page::as_select(), Book::as_select()))
+        .explain(conn)?;
+
+    print!("{}", plan);
+    Ok(())
+}
@@ -23,7 +23,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 把 grouped_books 和 all_authors 组合，转成 (Author, Vec<Book>) 形式
     let books_per_author: Vec<(Author, Vec<Book>)> = grouped_books
     .into_iter()
-    .zip(all_authors.into_iter())
+    .zip(all_authors)
     .map(|(book_authors, author)| {
         // book_authors 是 Vec<(BookAuthor, Book)>，取出第二个元素 Book 收集成 Vec<Book>
         let books = book_authors.into_iter().map(|(_, book)| book).collect();
@@ -0,0 +1,35 @@
+use ch09_features_relations::{models, pool::establish_connection, schema};
+use common::n_plus_one::NPlusOne;
+use diesel::prelude::*;
+use models::{Book, Page};
+
+fn main() -> Result<(), diesel::result::Error> {
+    use schema::books;
+
+    let conn = &mut establish_connection();
+    // 示例数据只有几本书，把阈值调低才能看到效果
+    let detector = NPlusOne::default().threshold(1);
+
+    // 每本书各查询一次页面
+    let (_, repeated) = detector.inspect(conn, |conn| -> QueryResult<()> { // [!code focus:7]
+        for book in books::table.select(Book::as_select()).load(conn)? {
+            let pages = Page::belonging_to(&book).select(Page::as_select()).load(conn)?;
+            println!("《{}》共 {} 页", book.title, pages.len());
+        }
+        Ok(())
+    });
+    for query in repeated {
+        print!("{}", query);
+    }
+
+    // 一次查询所有书的页面，不会被报告
+    let (_, repeated) = detector.inspect(conn, |conn| -> QueryResult<()> {
+        let all_books = books::table.select(Book::as_select()).load(conn)?;
+        let pages = Page::belonging_to(&all_books).select(Page::as_select()).load(conn)?;
+        println!("{} 本书共 {} 页", all_books.len(), pages.len());
+        Ok(())
+    });
+    println!("批量查询时重复的语句: {}", repeated.len());
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use ch09_features_relations::{models, pool::establish_connection, schema};
+use common::Paginate;
+use diesel::prelude::*;
+use models::{Book, Page};
+
+/// 分页查询的每一项需要是单个 Selectable 类型，通过 embed 组合 join 两侧的模型
+#[derive(Queryable, Selectable, Debug)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct PageWithBook {
+    #[diesel(embed)]
+    page: Page,
+    #[diesel(embed)]
+    book: Book,
+}
+
+fn main() -> Result<(), diesel::result::Error> {
+    use schema::{books, pages};
+
+    let conn = &mut establish_connection();
+
+    let result = pages::table // [!code focus:7]
+        .inner_join(books::table)
+        .order((books::id.asc(), pages::page_number.asc()))
+        .select(PageWithBook::as_select())
+        .paginate(1)
+        .per_page(2)
+        .load_page::<PageWithBook>(conn)?;
+
+    println!("第 {}/{} 页，共 {} 条", result.page, result.total_pages, result.total);
+    for PageWithBook { page, book } in result.items {
+        println!("《{}》第 {} 页: {}", book.title, page.page_number, page.content);
+    }
+
+    Ok(())
+}
@@ -1,5 +1,4 @@
 pub mod models;
 pub mod schema;
 pub mod pool;
-
-fn main() {}
\ No newline at end of file
+pub mod report;
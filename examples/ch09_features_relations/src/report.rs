@@ -0,0 +1,61 @@
+//! 书籍的统计报表：每本书的页数、每位作者的书籍数
+use crate::schema::{authors, books, books_authors, pages};
+use common::report::ReportRow;
+use diesel::dsl::count;
+use diesel::prelude::*;
+
+#[derive(Queryable, Debug)]
+pub struct PagesPerBook {
+    pub book_id: i32,
+    pub title: String,
+    pub pages: i64,
+}
+
+#[derive(Queryable, Debug)]
+pub struct BooksPerAuthor {
+    pub author_id: i32,
+    pub name: String,
+    pub books: i64,
+}
+
+// #region group_by
+/// 每本书的页数，没有任何页的书计为 0
+///
+/// 按主键 `books::id` 分组后，`books` 的其他列也可以出现在 `select` 中。
+pub fn pages_per_book(conn: &mut PgConnection) -> QueryResult<Vec<PagesPerBook>> {
+    books::table
+        .left_join(pages::table)
+        .group_by(books::id)
+        .select((books::id, books::title, count(pages::id.nullable())))
+        .order(books::id.asc())
+        .load(conn)
+}
+
+/// 通过 `books_authors` 统计每位作者参与的书籍数，只保留至少有 `min_books` 本书的作者
+pub fn books_per_author(conn: &mut PgConnection, min_books: i64) -> QueryResult<Vec<BooksPerAuthor>> {
+    let book_count = count(books_authors::book_id.nullable());
+    authors::table
+        .left_join(books_authors::table)
+        .group_by(authors::id)
+        .having(book_count.ge(min_books))
+        .select((authors::id, authors::name, book_count))
+        .order((book_count.desc(), authors::id.asc()))
+        .load(conn)
+}
+// #endregion group_by
+
+impl ReportRow for PagesPerBook {
+    const HEADERS: &'static [&'static str] = &["ID", "书名", "页数"];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.book_id.to_string(), self.title.clone(), self.pages.to_string()]
+    }
+}
+
+impl ReportRow for BooksPerAuthor {
+    const HEADERS: &'static [&'static str] = &["ID", "作者", "书籍数"];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.author_id.to_string(), self.name.clone(), self.books.to_string()]
+    }
+}
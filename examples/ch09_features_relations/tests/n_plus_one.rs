@@ -0,0 +1,57 @@
+//! 验证 `NPlusOne` 能在测试中发现 N+1 查询
+//!
+//! 需要数据库：`DATABASE_URL=... cargo test -p ch09_features_relations -- --include-ignored`。
+//! 示例数据写在测试事务中，连接关闭时回滚。
+use ch09_features_relations::models::{Book, Page};
+use ch09_features_relations::schema::{books, pages};
+use ch09_features_relations::pool::try_establish_connection;
+use common::n_plus_one::{NPlusOne, OnExceed};
+use diesel::prelude::*;
+
+/// 写入的书籍数量，每本书 2 页
+const BOOKS: i32 = 5;
+
+fn seeded_connection() -> PgConnection {
+    let mut conn = try_establish_connection().unwrap_or_else(|e| panic!("{}", e));
+    conn.begin_test_transaction().unwrap();
+    for n in 1..=BOOKS {
+        let book_id = diesel::insert_into(books::table)
+            .values(books::title.eq(format!("书 {}", n)))
+            .returning(books::id)
+            .get_result::<i32>(&mut conn)
+            .unwrap();
+        diesel::insert_into(pages::table)
+            .values(
+                (1..=2)
+                    .map(|page| (pages::page_number.eq(page), pages::content.eq("内容"), pages::book_id.eq(book_id)))
+                    .collect::<Vec<_>>(),
+            )
+            .execute(&mut conn)
+            .unwrap();
+    }
+    conn
+}
+
+#[test]
+#[ignore = "需要数据库"]
+#[should_panic(expected = "疑似 N+1 查询")]
+fn belonging_to_in_loop_panics() {
+    let conn = &mut seeded_connection();
+    NPlusOne::default().on_exceed(OnExceed::Panic).run(conn, |conn| {
+        for book in books::table.select(Book::as_select()).load(conn).unwrap() {
+            Page::belonging_to(&book).select(Page::as_select()).load(conn).unwrap();
+        }
+    });
+}
+
+#[test]
+#[ignore = "需要数据库"]
+fn grouped_loading_is_not_reported() {
+    let conn = &mut seeded_connection();
+    let (pages, repeated) = NPlusOne::default().inspect(conn, |conn| {
+        let all_books = books::table.select(Book::as_select()).load(conn).unwrap();
+        Page::belonging_to(&all_books).select(Page::as_select()).load(conn).unwrap()
+    });
+    assert!(pages.len() >= (BOOKS * 2) as usize);
+    assert!(repeated.is_empty(), "{:?}", repeated);
+}
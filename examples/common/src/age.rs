@@ -0,0 +1,19 @@
+use chrono::TimeDelta;
+
+/// 解析 `30m`、`12h`、`7d`、`2w` 形式的时间跨度，可直接用作 clap 的 `value_parser`
+pub fn parse_age(value: &str) -> Result<TimeDelta, String> {
+    let (split, _) = value.char_indices().last().ok_or("时间跨度不能为空")?;
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = amount
+        .parse()
+        .map_err(|_| format!("无效的时间跨度 \"{}\"，示例: 30m、12h、7d、2w", value))?;
+
+    let delta = match unit {
+        "m" => TimeDelta::try_minutes(amount),
+        "h" => TimeDelta::try_hours(amount),
+        "d" => TimeDelta::try_days(amount),
+        "w" => TimeDelta::try_weeks(amount),
+        _ => return Err(format!("未知的时间单位 \"{}\"，可选 m、h、d、w", unit)),
+    };
+    delta.ok_or_else(|| format!("时间跨度过大: {}", value))
+}
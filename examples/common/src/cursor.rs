@@ -0,0 +1,86 @@
+//! 用 Postgres 服务端游标分批读取大结果集
+//!
+//! `load` 一次把全部结果读入内存；`load_iter` 配合 `PgRowByRowLoadingMode` 虽然逐行处理，
+//! 但每取一行都要与服务器往返一次。服务端游标介于两者之间：
+//!
+//! ```sql
+//! BEGIN;
+//! DECLARE diesel_cursor NO SCROLL CURSOR FOR SELECT ...;
+//! FETCH FORWARD 1000 FROM diesel_cursor;  -- 重复直到取不到数据
+//! CLOSE diesel_cursor;
+//! COMMIT;
+//! ```
+//!
+//! 内存中最多只有 `fetch_size` 行，往返次数也减少为 `总行数 / fetch_size`。
+use crate::AppError;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_builder::{AstPass, QueryFragment, QueryId};
+use diesel::sql_query;
+
+/// 每次 FETCH 读取的默认行数
+pub const DEFAULT_FETCH_SIZE: i64 = 1000;
+
+/// 游标名称，同一个事务中同时只能打开一个
+const CURSOR_NAME: &str = "diesel_cursor";
+
+// #region declare
+/// `DECLARE diesel_cursor NO SCROLL CURSOR FOR <query>`，原查询的绑定参数保持不变
+struct DeclareCursor<Q> {
+    query: Q,
+}
+
+impl<Q> QueryId for DeclareCursor<Q> {
+    type QueryId = ();
+    const HAS_STATIC_QUERY_ID: bool = false;
+}
+
+impl<Q: QueryFragment<Pg>> QueryFragment<Pg> for DeclareCursor<Q> {
+    fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, Pg>) -> QueryResult<()> {
+        out.push_sql("DECLARE ");
+        out.push_identifier(CURSOR_NAME)?;
+        out.push_sql(" NO SCROLL CURSOR FOR ");
+        self.query.walk_ast(out.reborrow())
+    }
+}
+
+impl<Q> RunQueryDsl<PgConnection> for DeclareCursor<Q> {}
+// #endregion declare
+
+// #region fetch
+/// 在事务中为 `query` 打开游标，每次取 `fetch_size` 行交给 `f` 处理，返回处理的总行数
+///
+/// 结果按列名映射到 `U`，因此 `U` 需要实现 `QueryableByName`。`f` 返回错误时停止读取并回滚事务。
+pub fn for_each_fetched<Q, U, F>(conn: &mut PgConnection, query: Q, fetch_size: i64, mut f: F) -> Result<usize, AppError>
+where
+    Q: QueryFragment<Pg>,
+    U: QueryableByName<Pg> + 'static,
+    F: FnMut(U) -> Result<(), AppError>,
+{
+    if fetch_size < 1 {
+        return Err(AppError::Validation("fetch_size 必须大于 0".into()));
+    }
+
+    conn.transaction(|conn| {
+        DeclareCursor { query }.execute(conn)?;
+
+        // FETCH 的行数不能作为绑定参数，这里的 fetch_size 是整数，可以直接拼接
+        let fetch = format!("FETCH FORWARD {} FROM {}", fetch_size, CURSOR_NAME);
+        let mut count = 0;
+        loop {
+            let rows = sql_query(&fetch).load::<U>(conn)?;
+            let fetched = rows.len();
+            for row in rows {
+                f(row)?;
+            }
+            count += fetched;
+            if (fetched as i64) < fetch_size {
+                break;
+            }
+        }
+
+        sql_query(format!("CLOSE {}", CURSOR_NAME)).execute(conn)?;
+        Ok(count)
+    })
+}
+// #endregion fetch
@@ -0,0 +1,67 @@
+//! 在事务中试运行 UPDATE / DELETE 语句并回滚，用于预览语句会影响哪些行
+use diesel::debug_query;
+use diesel::pg::{Pg, PgConnection, PgRowByRowLoadingMode};
+use diesel::prelude::*;
+use diesel::query_builder::QueryFragment;
+use diesel::query_dsl::LoadQuery;
+use diesel::result::Error as DieselError;
+
+use crate::AppError;
+
+/// 试运行的结果
+pub struct Preview<T> {
+    /// 由 `debug_query` 生成的 SQL，包含绑定参数
+    pub sql: String,
+    /// 语句实际会影响的行数
+    pub affected: usize,
+    /// 受影响行中的前若干行
+    pub sample: Vec<T>,
+}
+
+// 借助事务闭包的错误通道把预览结果带出事务，同时保证事务被回滚
+enum Outcome<T> {
+    Done(Preview<T>),
+    Failed(DieselError),
+}
+
+impl<T> From<DieselError> for Outcome<T> {
+    fn from(err: DieselError) -> Self {
+        Outcome::Failed(err)
+    }
+}
+
+/// 执行带 `RETURNING` 的 UPDATE / DELETE 语句，统计受影响的行并保留最多 `sample_size` 行样本，随后回滚
+///
+/// ```ignore
+/// let statement = diesel::delete(posts.filter(title.like("%Rust%"))).returning(Post::as_returning());
+/// let preview = dry_run(conn, statement, 5)?;
+/// ```
+pub fn dry_run<'q, Q, T>(conn: &mut PgConnection, statement: Q, sample_size: usize) -> Result<Preview<T>, AppError>
+where
+    Q: QueryFragment<Pg> + LoadQuery<'q, PgConnection, T, PgRowByRowLoadingMode> + 'q,
+    T: 'static,
+{
+    let sql = debug_query::<Pg, _>(&statement).to_string();
+
+    let outcome = conn.transaction::<(), Outcome<T>, _>(|conn| {
+        let mut affected = 0;
+        let mut sample = Vec::with_capacity(sample_size);
+        // 默认加载模式下 libpq 会先把整个结果集读入内存，逐行加载模式每次只从服务器取一行，
+        // 受影响的行再多也只在内存中保留样本
+        for row in statement.load_iter::<T, PgRowByRowLoadingMode>(conn)? {
+            let row = row?;
+            if sample.len() < sample_size {
+                sample.push(row);
+            }
+            affected += 1;
+        }
+
+        Err(Outcome::Done(Preview { sql, affected, sample }))
+    });
+
+    match outcome {
+        Err(Outcome::Done(preview)) => Ok(preview),
+        Err(Outcome::Failed(err)) => Err(err.into()),
+        Ok(()) => unreachable!("试运行的事务总是回滚"),
+    }
+}
@@ -0,0 +1,96 @@
+use diesel::ConnectionError;
+use diesel::r2d2::PoolError;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum AppError {
+    /// 缺少必需的环境变量
+    MissingConfig(&'static str),
+    /// 无法建立数据库连接
+    Connection(ConnectionError),
+    /// 无法建立连接池
+    Pool(PoolError),
+    /// 要查找的记录不存在，内容为记录描述，如 "文章 1"
+    NotFound(String),
+    UniqueViolation(String),
+    ForeignKeyViolation(String),
+    /// 输入数据未通过校验
+    Validation(String),
+    /// 执行或回滚迁移失败
+    Migration(String),
+    /// 数据库中存在当前二进制未内嵌的迁移版本
+    UnknownMigrations(Vec<String>),
+    Database(DieselError),
+    /// 读写文件失败
+    Io(io::Error),
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::MissingConfig(name) => write!(f, "{} must be set", name),
+            AppError::Connection(e) => write!(f, "Error connecting to database: {}", e),
+            AppError::Pool(e) => write!(f, "Failed to build pool: {}", e),
+            AppError::NotFound(what) => write!(f, "找不到{}", what),
+            AppError::UniqueViolation(msg) => write!(f, "违反唯一约束: {}", msg),
+            AppError::ForeignKeyViolation(msg) => write!(f, "违反外键约束: {}", msg),
+            AppError::Validation(msg) => write!(f, "数据校验失败: {}", msg),
+            AppError::Migration(msg) => write!(f, "迁移失败: {}", msg),
+            AppError::UnknownMigrations(versions) => write!(
+                f,
+                "数据库中存在当前程序未知的迁移 {}，请确认 DATABASE_URL 或升级程序后再启动",
+                versions.join(", ")
+            ),
+            AppError::Database(e) => write!(f, "数据库错误: {}", e),
+            AppError::Io(e) => write!(f, "IO 错误: {}", e),
+        }
+    }
+}
+
+impl Error for AppError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AppError::Connection(e) => Some(e),
+            AppError::Pool(e) => Some(e),
+            AppError::Database(e) => Some(e),
+            AppError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<DieselError> for AppError {
+    fn from(err: DieselError) -> Self {
+        match err {
+            DieselError::NotFound => AppError::NotFound("记录".into()),
+            DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, info) => {
+                AppError::UniqueViolation(info.message().to_string())
+            }
+            DieselError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, info) => {
+                AppError::ForeignKeyViolation(info.message().to_string())
+            }
+            err => AppError::Database(err),
+        }
+    }
+}
+
+impl From<ConnectionError> for AppError {
+    fn from(err: ConnectionError) -> Self {
+        AppError::Connection(err)
+    }
+}
+
+impl From<PoolError> for AppError {
+    fn from(err: PoolError) -> Self {
+        AppError::Pool(err)
+    }
+}
+
+impl From<io::Error> for AppError {
+    fn from(err: io::Error) -> Self {
+        AppError::Io(err)
+    }
+}
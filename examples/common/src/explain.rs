@@ -0,0 +1,226 @@
+//! 用 `EXPLAIN (ANALYZE, BUFFERS, FORMAT JSON)` 分析任意查询的执行计划
+//!
+//! ```ignore
+//! let plan = posts::table.filter(posts::published.eq(true)).explain(conn)?;
+//! println!("{}", plan);
+//! ```
+//!
+//! `ANALYZE` 会真正执行查询，因此整个过程放在一个最终回滚的事务中，分析 `UPDATE`、`DELETE` 也不会修改数据。
+use crate::AppError;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_builder::{AstPass, Query, QueryFragment, QueryId};
+use diesel::sql_types::Text;
+use serde::Deserialize;
+use std::fmt;
+
+pub trait Explain: Sized {
+    /// 执行查询并返回实际的执行计划，查询造成的修改会被回滚
+    fn explain(self, conn: &mut PgConnection) -> Result<QueryPlan, AppError>;
+}
+
+impl<T: QueryFragment<Pg>> Explain for T {
+    fn explain(self, conn: &mut PgConnection) -> Result<QueryPlan, AppError> {
+        let query = ExplainQuery { query: self };
+        let mut output = None;
+        // 返回 RollbackTransaction 让 diesel 回滚事务，执行结果通过 output 带出
+        let rolled_back = conn.transaction::<(), diesel::result::Error, _>(|conn| {
+            output = Some(query.get_result::<String>(conn));
+            Err(diesel::result::Error::RollbackTransaction)
+        });
+        match rolled_back {
+            Err(diesel::result::Error::RollbackTransaction) => {}
+            Err(e) => return Err(e.into()),
+            Ok(()) => unreachable!("事务总是被回滚"),
+        }
+
+        let json = output.expect("事务中已经执行过查询")?;
+        // 输出是只有一个元素的数组
+        let mut plans: Vec<QueryPlan> = serde_json::from_str(&json)
+            .map_err(|e| AppError::Validation(format!("无法解析执行计划: {}", e)))?;
+        plans.pop().ok_or_else(|| AppError::Validation("执行计划为空".into()))
+    }
+}
+
+// #region explain_query
+/// `EXPLAIN (ANALYZE, BUFFERS, FORMAT JSON) <query>`，结果是一行 JSON 文本
+struct ExplainQuery<T> {
+    query: T,
+}
+
+impl<T> QueryId for ExplainQuery<T> {
+    type QueryId = ();
+    const HAS_STATIC_QUERY_ID: bool = false;
+}
+
+impl<T> Query for ExplainQuery<T> {
+    type SqlType = Text;
+}
+
+impl<T: QueryFragment<Pg>> QueryFragment<Pg> for ExplainQuery<T> {
+    fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, Pg>) -> QueryResult<()> {
+        out.push_sql("EXPLAIN (ANALYZE, BUFFERS, FORMAT JSON) ");
+        self.query.walk_ast(out.reborrow())
+    }
+}
+
+impl<T> RunQueryDsl<PgConnection> for ExplainQuery<T> {}
+// #endregion explain_query
+
+#[derive(Debug, Deserialize)]
+pub struct QueryPlan {
+    #[serde(rename = "Plan")]
+    pub root: PlanNode,
+    /// 毫秒
+    #[serde(rename = "Planning Time")]
+    pub planning_time: f64,
+    /// 毫秒
+    #[serde(rename = "Execution Time")]
+    pub execution_time: f64,
+}
+
+/// 执行计划中的一个节点，只保留常用的字段
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PlanNode {
+    #[serde(rename = "Node Type")]
+    pub node_type: String,
+    #[serde(rename = "Relation Name")]
+    pub relation_name: Option<String>,
+    #[serde(rename = "Index Name")]
+    pub index_name: Option<String>,
+    pub filter: Option<String>,
+    #[serde(rename = "Total Cost")]
+    pub total_cost: f64,
+    /// 估计的行数
+    #[serde(rename = "Plan Rows")]
+    pub plan_rows: f64,
+    /// 实际的行数，是每次循环的平均值
+    #[serde(rename = "Actual Rows", default)]
+    pub actual_rows: f64,
+    #[serde(rename = "Actual Loops", default)]
+    pub actual_loops: f64,
+    /// 毫秒，是每次循环的平均值
+    #[serde(rename = "Actual Total Time", default)]
+    pub actual_total_time: f64,
+    #[serde(rename = "Rows Removed by Filter", default)]
+    pub rows_removed_by_filter: f64,
+    #[serde(rename = "Shared Hit Blocks", default)]
+    pub shared_hit_blocks: i64,
+    #[serde(rename = "Shared Read Blocks", default)]
+    pub shared_read_blocks: i64,
+    #[serde(default)]
+    pub plans: Vec<PlanNode>,
+}
+
+/// 判断执行计划中可疑节点的阈值
+#[derive(Debug, Clone, Copy)]
+pub struct ExplainThresholds {
+    /// 顺序扫描读取的行数达到该值时视为扫描大表
+    pub large_scan_rows: f64,
+    /// 估计行数与实际行数相差达到该倍数时提示
+    pub row_mismatch_factor: f64,
+}
+
+impl Default for ExplainThresholds {
+    fn default() -> Self {
+        ExplainThresholds {
+            large_scan_rows: 10_000.0,
+            row_mismatch_factor: 10.0,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PlanWarning {
+    /// 对大表做了顺序扫描，`rows` 为扫描过的行数
+    SeqScan { relation: String, rows: f64 },
+    /// 估计行数与实际行数相差太大，通常意味着统计信息过期，需要 `ANALYZE`
+    RowMismatch { node: String, estimated: f64, actual: f64 },
+}
+
+impl PlanNode {
+    /// 节点扫描过的总行数：返回的行加上被过滤掉的行，乘以循环次数
+    pub fn rows_scanned(&self) -> f64 {
+        (self.actual_rows + self.rows_removed_by_filter) * self.actual_loops.max(1.0)
+    }
+
+    fn label(&self) -> String {
+        match (&self.relation_name, &self.index_name) {
+            (Some(relation), Some(index)) => format!("{} using {} on {}", self.node_type, index, relation),
+            (Some(relation), None) => format!("{} on {}", self.node_type, relation),
+            _ => self.node_type.clone(),
+        }
+    }
+}
+
+// #region warnings
+impl QueryPlan {
+    /// 按深度优先的顺序找出顺序扫描大表、估计行数严重偏离的节点
+    pub fn warnings(&self, thresholds: ExplainThresholds) -> Vec<PlanWarning> {
+        let mut warnings = Vec::new();
+        let mut stack = vec![&self.root];
+        while let Some(node) = stack.pop() {
+            if node.node_type == "Seq Scan" && node.rows_scanned() >= thresholds.large_scan_rows {
+                warnings.push(PlanWarning::SeqScan {
+                    relation: node.relation_name.clone().unwrap_or_default(),
+                    rows: node.rows_scanned(),
+                });
+            }
+
+            // 没有执行到的节点（actual_loops 为 0）不比较
+            let (estimated, actual) = (node.plan_rows.max(1.0), node.actual_rows.max(1.0));
+            if node.actual_loops > 0.0 && estimated.max(actual) / estimated.min(actual) >= thresholds.row_mismatch_factor {
+                warnings.push(PlanWarning::RowMismatch { node: node.label(), estimated: node.plan_rows, actual: node.actual_rows });
+            }
+
+            stack.extend(node.plans.iter().rev());
+        }
+        warnings
+    }
+}
+// #endregion warnings
+
+/// 缩进的计划树，之后是按默认阈值找出的问题
+impl fmt::Display for QueryPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_node(f, &self.root, 0)?;
+        writeln!(f, "规划耗时: {:.3} ms，执行耗时: {:.3} ms", self.planning_time, self.execution_time)?;
+
+        for warning in self.warnings(ExplainThresholds::default()) {
+            match warning {
+                PlanWarning::SeqScan { relation, rows } => {
+                    writeln!(f, "警告: 对 {} 做了顺序扫描，扫描了 {} 行，考虑添加索引", relation, rows)?
+                }
+                PlanWarning::RowMismatch { node, estimated, actual } => {
+                    writeln!(f, "警告: {} 估计 {} 行，实际 {} 行，统计信息可能已过期", node, estimated, actual)?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn fmt_node(f: &mut fmt::Formatter<'_>, node: &PlanNode, depth: usize) -> fmt::Result {
+    let indent = "  ".repeat(depth);
+    writeln!(
+        f,
+        "{}-> {}  (cost={:.2} 估计 {} 行) (实际 {} 行 x {} 次, {:.3} ms) (缓存命中 {} 块, 读取 {} 块)",
+        indent,
+        node.label(),
+        node.total_cost,
+        node.plan_rows,
+        node.actual_rows,
+        node.actual_loops,
+        node.actual_total_time,
+        node.shared_hit_blocks,
+        node.shared_read_blocks
+    )?;
+    if let Some(filter) = &node.filter {
+        writeln!(f, "{}     过滤: {}（移除 {} 行）", indent, filter, node.rows_removed_by_filter)?;
+    }
+    for child in &node.plans {
+        fmt_node(f, child, depth + 1)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,56 @@
+//! 各示例 crate 共享的数据库连接与错误类型
+use diesel::prelude::*;
+use diesel_migrations::EmbeddedMigrations;
+use dotenvy::dotenv;
+use std::env;
+
+pub mod age;
+pub mod cursor;
+pub mod dry_run;
+pub mod error;
+pub mod explain;
+pub mod migrations;
+pub mod n_plus_one;
+pub mod pagination;
+pub mod query_log;
+pub mod report;
+
+pub use age::parse_age;
+pub use error::AppError;
+pub use explain::Explain;
+pub use pagination::{Page, Paginate};
+
+/// 读取 `DATABASE_URL`，同时加载 .env 文件
+pub fn database_url() -> Result<String, AppError> {
+    // 让我们可以获取环境变量 .env 内容
+    dotenv().ok();
+
+    env::var("DATABASE_URL").map_err(|_| AppError::MissingConfig("DATABASE_URL"))
+}
+
+/// 建立连接，连接上执行的 SQL 会通过 `query_log` 记录
+pub fn try_establish_connection() -> Result<PgConnection, AppError> {
+    let database_url = database_url()?;
+    query_log::install_from_env();
+
+    PgConnection::establish(&database_url).map_err(AppError::Connection)
+}
+
+/// 示例程序使用的便捷版本，连接失败时直接 panic
+pub fn establish_connection() -> PgConnection {
+    try_establish_connection().unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// 建立连接并切换到示例自己的 `schema`，见 `migrations::use_schema`
+pub fn try_establish_schema_connection(schema: &str) -> Result<PgConnection, AppError> {
+    let mut conn = try_establish_connection()?;
+    migrations::use_schema(&mut conn, schema)?;
+    Ok(conn)
+}
+
+/// 建立连接并切换到 `schema`，再执行 `migrations` 中尚未执行的迁移
+pub fn try_establish_migrated_connection(schema: &str, migrations: &EmbeddedMigrations) -> Result<PgConnection, AppError> {
+    let mut conn = try_establish_schema_connection(schema)?;
+    migrations::run_pending(&mut conn, migrations)?;
+    Ok(conn)
+}
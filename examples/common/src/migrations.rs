@@ -0,0 +1,180 @@
+//! 内嵌迁移的执行、回滚与状态查询
+//!
+//! 各示例 crate 通过 `embed_migrations!("migrations")` 把自己的 `migrations/` 目录编译进二进制，
+//! 再把生成的 `EmbeddedMigrations` 交给这里的函数执行，无需安装 diesel CLI。
+//!
+//! 各章节的迁移并不相同，因此每个示例在同一个数据库中使用自己的 schema（见 `use_schema`），
+//! 表与 `__diesel_schema_migrations` 记录互不影响，`ensure_known` 只会看到本章节执行过的迁移。
+//!
+//! 多个进程（如 `blog` 与 `publish_worker`）或连接池中的多个连接可能同时启动并执行迁移。
+//! 检查与执行迁移都在同一个事务中进行，并先获取当前 schema 的事务级 advisory lock，
+//! 后启动的一方等待前者提交后再检查，不会重复执行同一个迁移。
+use diesel::connection::SimpleConnection;
+use diesel::prelude::*;
+use diesel::migration::{Migration, MigrationSource};
+use diesel::pg::{Pg, PgConnection};
+use diesel_migrations::{EmbeddedMigrations, MigrationHarness};
+
+use crate::AppError;
+
+/// 单个迁移的状态
+pub struct MigrationStatus {
+    pub name: String,
+    pub applied: bool,
+}
+
+pub struct StatusReport {
+    /// 二进制内嵌的全部迁移，按版本升序排列
+    pub migrations: Vec<MigrationStatus>,
+    /// 数据库中已执行、但二进制并不认识的迁移版本
+    pub unknown: Vec<String>,
+}
+
+/// 切换到 `schema`（不存在时创建），之后未限定 schema 的建表、查询与迁移记录都在其中
+///
+/// `search_path` 只包含这一个 schema，缺少的表会直接报错，而不会悄悄读到其他章节的同名表。
+pub fn use_schema(conn: &mut PgConnection, schema: &str) -> QueryResult<()> {
+    let quoted = format!("\"{}\"", schema.replace('"', "\"\""));
+    // 并发执行 CREATE SCHEMA IF NOT EXISTS 仍可能违反唯一约束，同样先获取锁
+    conn.transaction(|conn| {
+        lock_schema(conn, schema)?;
+        conn.batch_execute(&format!("CREATE SCHEMA IF NOT EXISTS {}", quoted))
+    })?;
+    conn.batch_execute(&format!("SET search_path TO {}", quoted))
+}
+
+/// 获取 `schema` 的事务级 advisory lock，事务结束时自动释放
+fn lock_schema(conn: &mut PgConnection, schema: &str) -> QueryResult<()> {
+    diesel::sql_query("SELECT pg_advisory_xact_lock(hashtextextended('diesel_migrations:' || $1, 0))")
+        .bind::<diesel::sql_types::Text, _>(schema)
+        .execute(conn)
+        .map(drop)
+}
+
+/// 在持有当前 schema 迁移锁的事务中执行 `f`
+fn locked<T>(
+    conn: &mut PgConnection,
+    f: impl FnOnce(&mut PgConnection) -> Result<T, AppError>,
+) -> Result<T, AppError> {
+    conn.transaction(|conn| {
+        let schema = diesel::select(current_schema()).get_result::<String>(conn)?;
+        lock_schema(conn, &schema)?;
+        f(conn)
+    })
+}
+
+define_sql_function! {
+    fn current_schema() -> diesel::sql_types::Text;
+}
+
+/// 检查数据库状态后执行所有尚未执行的迁移，返回本次执行的迁移名称
+///
+/// 数据库中存在二进制不认识的迁移时拒绝执行，说明数据库来自更新的版本或其他项目。
+pub fn run_pending(conn: &mut PgConnection, source: &EmbeddedMigrations) -> Result<Vec<String>, AppError> {
+    locked(conn, |conn| {
+        ensure_known(conn, source)?;
+
+        let applied = applied_versions(conn)?;
+        let mut executed = Vec::new();
+        for migration in load(source)? {
+            let name = migration.name().to_string();
+            if !applied.contains(&version_of(&*migration)) {
+                conn.run_migration(&*migration).map_err(migration_error)?;
+                executed.push(name);
+            }
+        }
+
+        Ok(executed)
+    })
+}
+
+/// 回滚最近执行的一个迁移，返回其名称
+pub fn revert_last(conn: &mut PgConnection, source: &EmbeddedMigrations) -> Result<String, AppError> {
+    locked(conn, |conn| {
+        ensure_known(conn, source)?;
+
+        let migration = last_applied(conn, source)?;
+        conn.revert_migration(&*migration).map_err(migration_error)?;
+        Ok(migration.name().to_string())
+    })
+}
+
+/// 回滚最近执行的迁移并重新执行，用于验证 `down.sql` 是否正确
+pub fn redo(conn: &mut PgConnection, source: &EmbeddedMigrations) -> Result<String, AppError> {
+    locked(conn, |conn| {
+        ensure_known(conn, source)?;
+
+        let migration = last_applied(conn, source)?;
+        conn.revert_migration(&*migration).map_err(migration_error)?;
+        conn.run_migration(&*migration).map_err(migration_error)?;
+        Ok(migration.name().to_string())
+    })
+}
+
+pub fn status(conn: &mut PgConnection, source: &EmbeddedMigrations) -> Result<StatusReport, AppError> {
+    let applied = applied_versions(conn)?;
+    let migrations = load(source)?;
+
+    let unknown = unknown_versions(&applied, &migrations);
+    let migrations = migrations
+        .iter()
+        .map(|m| MigrationStatus {
+            name: m.name().to_string(),
+            applied: applied.contains(&version_of(&**m)),
+        })
+        .collect();
+
+    Ok(StatusReport { migrations, unknown })
+}
+
+/// 数据库中存在二进制不认识的迁移时返回 `AppError::UnknownMigrations`
+pub fn ensure_known(conn: &mut PgConnection, source: &EmbeddedMigrations) -> Result<(), AppError> {
+    let applied = applied_versions(conn)?;
+    let unknown = unknown_versions(&applied, &load(source)?);
+
+    if unknown.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::UnknownMigrations(unknown))
+    }
+}
+
+fn load(source: &EmbeddedMigrations) -> Result<Vec<Box<dyn Migration<Pg>>>, AppError> {
+    let mut migrations = MigrationSource::<Pg>::migrations(source).map_err(migration_error)?;
+    migrations.sort_unstable_by(|a, b| a.name().version().cmp(&b.name().version()));
+    Ok(migrations)
+}
+
+fn applied_versions(conn: &mut PgConnection) -> Result<Vec<String>, AppError> {
+    let versions = conn.applied_migrations().map_err(migration_error)?;
+    Ok(versions.iter().map(|v| v.to_string()).collect())
+}
+
+fn last_applied(conn: &mut PgConnection, source: &EmbeddedMigrations) -> Result<Box<dyn Migration<Pg>>, AppError> {
+    // applied_migrations 按版本降序返回，第一个即最近执行的迁移
+    let last = applied_versions(conn)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| AppError::Migration("没有已执行的迁移".into()))?;
+
+    load(source)?
+        .into_iter()
+        .find(|m| version_of(&**m) == last)
+        .ok_or(AppError::UnknownMigrations(vec![last]))
+}
+
+fn unknown_versions(applied: &[String], migrations: &[Box<dyn Migration<Pg>>]) -> Vec<String> {
+    applied
+        .iter()
+        .filter(|v| !migrations.iter().any(|m| version_of(&**m) == **v))
+        .cloned()
+        .collect()
+}
+
+fn version_of(migration: &dyn Migration<Pg>) -> String {
+    migration.name().version().to_string()
+}
+
+fn migration_error(err: Box<dyn std::error::Error + Send + Sync>) -> AppError {
+    AppError::Migration(err.to_string())
+}
@@ -0,0 +1,334 @@
+//! 开发时检测 N+1 查询
+//!
+//! 在循环中调用 `Page::belonging_to(&book)` 会为每本书执行一次形状相同的查询。`NPlusOne::run`
+//! 在一段代码（一个工作单元）执行期间按归一化后的 SQL 统计语句，同一形状执行超过阈值时发出警告，
+//! 或者直接 panic 让测试失败，并给出这些语句的调用位置。
+//!
+//! diesel 无法取回连接上已有的 instrumentation，替换之后也就无法原样恢复，因此这里不替换连接的
+//! instrumentation：`query_log::QueryLogger`（`common` 建立的连接都会使用它）在每条语句开始时调用
+//! `record`，把语句记到当前线程正在检测的工作单元中。使用其他 instrumentation 的连接不会被检测，
+//! 一个工作单元结束时没有记录到任何语句会发出警告。
+//!
+//! 收集调用位置需要捕获调用栈，开销较大，因此只在 debug 构建中检测，release 构建中 `run` 直接执行闭包。
+use diesel::connection::DebugQuery;
+use diesel::prelude::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+
+/// 日志目标
+pub const TARGET: &str = "diesel::n_plus_one";
+
+/// 同一形状的语句默认允许执行的次数
+pub const DEFAULT_THRESHOLD: usize = 3;
+
+/// 每种语句最多记录的调用位置数量
+const MAX_CALL_SITES: usize = 5;
+
+/// 每种语句最多捕获调用栈的次数，典型的 N+1 只有一个调用位置，不需要每次执行都捕获
+const MAX_CAPTURES: usize = 10;
+
+thread_local! {
+    /// 当前线程上正在检测的工作单元，`None` 表示没有在检测
+    static ACTIVE: RefCell<Option<UnitOfWork>> = const { RefCell::new(None) };
+}
+
+/// 一个工作单元期间记录的语句
+#[derive(Debug, Default)]
+struct UnitOfWork {
+    /// 记录到的语句总数，包括不参与统计的事务语句
+    statements: usize,
+    shapes: HashMap<String, ShapeStats>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnExceed {
+    /// 以 `WARN` 级别记录
+    Warn,
+    /// panic，适合在测试中使用
+    Panic,
+}
+
+/// 执行次数超过阈值的一种语句
+#[derive(Debug, Clone)]
+pub struct RepeatedQuery {
+    /// 归一化后的 SQL
+    pub shape: String,
+    pub count: usize,
+    /// 形如 `./src/bin/n_plus_one.rs:18:14` 的调用位置，去重后按首次出现的顺序排列
+    pub call_sites: Vec<String>,
+}
+
+impl fmt::Display for RepeatedQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "以下语句执行了 {} 次: {}", self.count, self.shape)?;
+        for site in &self.call_sites {
+            writeln!(f, "    at {}", site)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct NPlusOne {
+    threshold: usize,
+    on_exceed: OnExceed,
+}
+
+impl Default for NPlusOne {
+    fn default() -> Self {
+        NPlusOne { threshold: DEFAULT_THRESHOLD, on_exceed: OnExceed::Warn }
+    }
+}
+
+impl NPlusOne {
+    /// 同一形状的语句执行次数超过 `threshold` 时视为 N+1
+    pub fn threshold(self, threshold: usize) -> Self {
+        NPlusOne { threshold, ..self }
+    }
+
+    pub fn on_exceed(self, on_exceed: OnExceed) -> Self {
+        NPlusOne { on_exceed, ..self }
+    }
+
+    // #region run
+    /// 执行 `f`，并按 `on_exceed` 处理其中重复执行的语句
+    pub fn run<T>(&self, conn: &mut PgConnection, f: impl FnOnce(&mut PgConnection) -> T) -> T {
+        let (result, repeated) = self.inspect(conn, f);
+        for query in &repeated {
+            match self.on_exceed {
+                OnExceed::Warn => tracing::warn!(target: TARGET, "疑似 N+1 查询\n{}", query),
+                OnExceed::Panic => panic!("疑似 N+1 查询\n{}", query),
+            }
+        }
+        result
+    }
+    // #endregion run
+
+    /// 执行 `f`，同时返回执行次数超过阈值的语句
+    ///
+    /// 统计的是 `f` 执行期间当前线程上执行的语句；`f` panic 时检测同样会结束。
+    /// 一条语句都没有记录到时以 `WARN` 级别提示，通常说明连接没有使用 `QueryLogger`。
+    pub fn inspect<T>(&self, conn: &mut PgConnection, f: impl FnOnce(&mut PgConnection) -> T) -> (T, Vec<RepeatedQuery>) {
+        if !cfg!(debug_assertions) {
+            return (f(conn), Vec::new());
+        }
+
+        let guard = ActiveGuard::start();
+        let result = f(conn);
+        let unit = guard.finish();
+        if unit.statements == 0 {
+            // 连接没有使用 `QueryLogger` 时检测不到任何语句，结果并不代表没有 N+1
+            tracing::warn!(target: TARGET, "检测期间没有记录到任何语句，请确认连接使用了 query_log::QueryLogger");
+        }
+
+        let mut repeated: Vec<RepeatedQuery> = unit
+            .shapes
+            .into_iter()
+            .filter(|(_, stats)| stats.count > self.threshold)
+            .map(|(shape, stats)| RepeatedQuery { shape, count: stats.count, call_sites: stats.call_sites })
+            .collect();
+        repeated.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.shape.cmp(&b.shape)));
+        (result, repeated)
+    }
+}
+
+/// 开始一个工作单元，离开作用域时（包括 panic）恢复之前的状态，嵌套的 `inspect` 结束后外层继续统计
+struct ActiveGuard {
+    previous: Option<Option<UnitOfWork>>,
+}
+
+impl ActiveGuard {
+    fn start() -> Self {
+        let previous = ACTIVE.with(|active| active.replace(Some(UnitOfWork::default())));
+        ActiveGuard { previous: Some(previous) }
+    }
+
+    /// 结束工作单元，返回期间的统计
+    fn finish(mut self) -> UnitOfWork {
+        let previous = self.previous.take().unwrap_or_default();
+        ACTIVE.with(|active| active.replace(previous)).unwrap_or_default()
+    }
+}
+
+impl Drop for ActiveGuard {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            ACTIVE.with(|active| *active.borrow_mut() = previous);
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ShapeStats {
+    count: usize,
+    /// 已经捕获调用栈的次数
+    captures: usize,
+    call_sites: Vec<String>,
+}
+
+// #region detector
+/// 把语句记到当前线程正在检测的工作单元中，没有在检测时直接返回
+///
+/// 由 `query_log::QueryLogger` 在每条语句开始时调用。
+pub(crate) fn record(query: &dyn DebugQuery) {
+    ACTIVE.with(|active| {
+        let mut active = active.borrow_mut();
+        let Some(unit) = active.as_mut() else {
+            return;
+        };
+        unit.statements += 1;
+
+        let sql = query.to_string();
+        let sql = sql.split_once(" -- binds: ").map_or(sql.as_str(), |(sql, _)| sql);
+        let shape = normalize_sql(sql);
+        // 事务语句在循环中重复出现是正常的
+        if is_transaction_statement(&shape) {
+            return;
+        }
+
+        let entry = unit.shapes.entry(shape).or_default();
+        entry.count += 1;
+        if entry.call_sites.len() < MAX_CALL_SITES && entry.captures < MAX_CAPTURES {
+            entry.captures += 1;
+            if let Some(site) = call_site()
+                && !entry.call_sites.contains(&site)
+            {
+                entry.call_sites.push(site);
+            }
+        }
+    });
+}
+// #endregion detector
+
+/// 把 SQL 归一化为形状：绑定参数、数字与字符串字面量替换为 `?`，连续空白合并为一个空格
+///
+/// 只有参数不同的语句得到相同的形状，例如用 `sql_query(format!(...))` 拼接 id 的查询。
+pub fn normalize_sql(sql: &str) -> String {
+    let mut shape = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    // 前一个字符是否属于标识符，用于区分 `t1` 中的数字与独立的数字字面量
+    let mut in_word = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                // 带引号的标识符原样保留
+                shape.push(c);
+                for c in chars.by_ref() {
+                    shape.push(c);
+                    if c == '"' {
+                        break;
+                    }
+                }
+                in_word = false;
+            }
+            '\'' => {
+                // `''` 是字符串中转义的单引号
+                while let Some(c) = chars.next() {
+                    if c == '\'' && chars.next_if_eq(&'\'').is_none() {
+                        break;
+                    }
+                }
+                shape.push('?');
+                in_word = false;
+            }
+            '$' if chars.peek().is_some_and(char::is_ascii_digit) => {
+                while chars.next_if(char::is_ascii_digit).is_some() {}
+                shape.push('?');
+                in_word = false;
+            }
+            c if c.is_ascii_digit() && !in_word => {
+                while chars.next_if(|c| c.is_ascii_digit() || *c == '.').is_some() {}
+                shape.push('?');
+            }
+            c if c.is_whitespace() => {
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                shape.push(' ');
+                in_word = false;
+            }
+            c => {
+                shape.push(c);
+                in_word = c.is_alphanumeric() || c == '_';
+            }
+        }
+    }
+    shape.trim().to_string()
+}
+
+fn is_transaction_statement(shape: &str) -> bool {
+    let keyword = shape.split(' ').next().unwrap_or_default().to_uppercase();
+    matches!(keyword.as_str(), "BEGIN" | "COMMIT" | "ROLLBACK" | "SAVEPOINT" | "RELEASE")
+}
+
+/// 调用 `record` 的模块，它的栈帧和本模块一样需要跳过
+const QUERY_LOG_MODULE: &str = concat!(env!("CARGO_CRATE_NAME"), "::query_log");
+
+/// 从调用栈中找出第一个不属于标准库、依赖库、本模块和查询日志的位置
+fn call_site() -> Option<String> {
+    let backtrace = std::backtrace::Backtrace::force_capture().to_string();
+    // 每一帧是一行函数名，之后是一行或多行 `at 文件:行:列`
+    let mut function = "";
+    for line in backtrace.lines().map(str::trim) {
+        match line.strip_prefix("at ") {
+            Some(location) => {
+                let external = location.starts_with("/rustc/") || location.contains("/.cargo/");
+                let detector = [module_path!(), QUERY_LOG_MODULE].iter().any(|module| function.contains(module));
+                if !external && !detector {
+                    return Some(location.to_string());
+                }
+            }
+            None => function = line,
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_bind_parameters() {
+        assert_eq!(
+            normalize_sql(r#"SELECT "pages"."id" FROM "pages" WHERE ("pages"."book_id" = $1) LIMIT $12"#),
+            r#"SELECT "pages"."id" FROM "pages" WHERE ("pages"."book_id" = ?) LIMIT ?"#
+        );
+    }
+
+    #[test]
+    fn replaces_numbers_but_not_digits_in_identifiers() {
+        assert_eq!(normalize_sql("SELECT t1.id FROM t1 WHERE t1.id = 42"), "SELECT t1.id FROM t1 WHERE t1.id = ?");
+        assert_eq!(normalize_sql("SELECT * FROM posts WHERE score > 3.5"), "SELECT * FROM posts WHERE score > ?");
+        assert_eq!(normalize_sql("SELECT col_2 FROM t WHERE x IN (1,2)"), "SELECT col_2 FROM t WHERE x IN (?,?)");
+    }
+
+    #[test]
+    fn replaces_string_literals_with_escaped_quotes() {
+        assert_eq!(normalize_sql("SELECT * FROM posts WHERE title = 'it''s'"), "SELECT * FROM posts WHERE title = ?");
+        assert_eq!(normalize_sql("SELECT * FROM posts WHERE title = ''"), "SELECT * FROM posts WHERE title = ?");
+        assert_eq!(
+            normalize_sql("SELECT * FROM posts WHERE title = 'a' AND body = 'b'"),
+            normalize_sql("SELECT * FROM posts WHERE title = 'x''y' AND body = '1 2'")
+        );
+    }
+
+    #[test]
+    fn keeps_quoted_identifiers() {
+        assert_eq!(normalize_sql(r#"SELECT "t 1"."col2" FROM "t 1""#), r#"SELECT "t 1"."col2" FROM "t 1""#);
+        assert_eq!(normalize_sql(r#"SELECT "it's" FROM t WHERE a = 1"#), r#"SELECT "it's" FROM t WHERE a = ?"#);
+    }
+
+    #[test]
+    fn collapses_whitespace() {
+        assert_eq!(normalize_sql("  SELECT *\n\tFROM   posts  "), "SELECT * FROM posts");
+    }
+
+    #[test]
+    fn recognizes_transaction_statements() {
+        for sql in ["BEGIN", "COMMIT", "rollback", "SAVEPOINT diesel_savepoint_1", "RELEASE SAVEPOINT diesel_savepoint_1"] {
+            assert!(is_transaction_statement(&normalize_sql(sql)), "{}", sql);
+        }
+        assert!(!is_transaction_statement("SELECT ?"));
+    }
+}
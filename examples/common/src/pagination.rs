@@ -0,0 +1,147 @@
+//! 为任意查询添加分页，并在同一次查询中取得总数
+//!
+//! ```ignore
+//! let page = posts::table
+//!     .filter(posts::published.eq(true))
+//!     .order(posts::id.asc())
+//!     .select(Post::as_select())
+//!     .paginate(2)
+//!     .per_page(10)
+//!     .load_page::<Post>(conn)?;
+//! ```
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_builder::{AstPass, Query, QueryFragment, QueryId};
+use diesel::query_dsl::methods::LoadQuery;
+use diesel::sql_types::BigInt;
+use serde::Serialize;
+
+pub const DEFAULT_PER_PAGE: i64 = 10;
+
+/// 分页查询的结果
+#[derive(Debug, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// 当前页码，从 1 开始
+    pub page: i64,
+    pub per_page: i64,
+    /// 满足条件的记录总数
+    pub total: i64,
+    pub total_pages: i64,
+}
+
+pub trait Paginate: Sized {
+    /// 取第 `page` 页（从 1 开始，小于 1 时按第 1 页处理）
+    fn paginate(self, page: i64) -> Paginated<Self>;
+}
+
+impl<T: Query> Paginate for T {
+    fn paginate(self, page: i64) -> Paginated<Self> {
+        let page = page.max(1);
+        Paginated {
+            query: self,
+            page,
+            per_page: DEFAULT_PER_PAGE,
+            // 页码来自用户输入，乘积可能溢出 i64
+            offset: (page - 1).saturating_mul(DEFAULT_PER_PAGE),
+        }
+    }
+}
+
+// #region paginated
+/// 把原查询包装为 `SELECT *, COUNT(*) OVER () FROM (原查询) t LIMIT $1 OFFSET $2`
+///
+/// 窗口函数 `COUNT(*) OVER ()` 在 LIMIT 之前计算，每一行都带上满足条件的总行数，
+/// 因此只需要一次查询。原查询可以是 boxed 查询，也可以是多表 join 的查询。
+#[derive(Debug, Clone, Copy, QueryId)]
+pub struct Paginated<T> {
+    query: T,
+    page: i64,
+    per_page: i64,
+    offset: i64,
+}
+
+impl<T> Paginated<T> {
+    /// 每页的记录数，小于 1 时按 1 处理
+    pub fn per_page(self, per_page: i64) -> Self {
+        let per_page = per_page.max(1);
+        Paginated {
+            per_page,
+            offset: (self.page - 1).saturating_mul(per_page),
+            ..self
+        }
+    }
+
+    pub fn load_page<'q, U>(&'q self, conn: &mut PgConnection) -> QueryResult<Page<U>>
+    where
+        Paginated<&'q T>: LoadQuery<'q, PgConnection, (U, i64)>,
+        CountQuery<&'q T>: LoadQuery<'q, PgConnection, i64>,
+    {
+        let (page, per_page) = (self.page, self.per_page);
+        let paginated = Paginated { query: &self.query, page, per_page, offset: self.offset };
+        let rows = paginated.load::<(U, i64)>(conn)?;
+
+        let total = match rows.first() {
+            Some((_, total)) => *total,
+            // 页码超出范围时没有任何行，窗口函数也就给不出总数，需要单独计数
+            None if page > 1 => CountQuery { query: &self.query }.get_result(conn)?,
+            None => 0,
+        };
+        let items = rows.into_iter().map(|(item, _)| item).collect();
+
+        Ok(Page {
+            items,
+            page,
+            per_page,
+            total,
+            // 不写成 `(total + per_page - 1) / per_page`，per_page 很大时会溢出
+            total_pages: if total == 0 { 0 } else { (total - 1) / per_page + 1 },
+        })
+    }
+}
+
+impl<T: Query> Query for Paginated<T> {
+    type SqlType = (T::SqlType, BigInt);
+}
+
+impl<T> RunQueryDsl<PgConnection> for Paginated<T> {}
+
+impl<T> QueryFragment<Pg> for Paginated<T>
+where
+    T: QueryFragment<Pg>,
+{
+    fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, Pg>) -> QueryResult<()> {
+        out.push_sql("SELECT *, COUNT(*) OVER () FROM (");
+        self.query.walk_ast(out.reborrow())?;
+        out.push_sql(") t LIMIT ");
+        out.push_bind_param::<BigInt, _>(&self.per_page)?;
+        out.push_sql(" OFFSET ");
+        out.push_bind_param::<BigInt, _>(&self.offset)?;
+        Ok(())
+    }
+}
+// #endregion paginated
+
+/// `SELECT COUNT(*) FROM (原查询) t`，页码超出范围时用来取得总数
+#[derive(Debug, Clone, Copy, QueryId)]
+pub struct CountQuery<T> {
+    query: T,
+}
+
+impl<T> Query for CountQuery<T> {
+    type SqlType = BigInt;
+}
+
+impl<T> RunQueryDsl<PgConnection> for CountQuery<T> {}
+
+impl<T> QueryFragment<Pg> for CountQuery<T>
+where
+    T: QueryFragment<Pg>,
+{
+    fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, Pg>) -> QueryResult<()> {
+        out.push_sql("SELECT COUNT(*) FROM (");
+        self.query.walk_ast(out.reborrow())?;
+        out.push_sql(") t");
+        Ok(())
+    }
+}
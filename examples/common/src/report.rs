@@ -0,0 +1,63 @@
+//! 以对齐的表格或 CSV 输出统计报表
+use crate::AppError;
+use std::io::{self, Write};
+use unicode_width::UnicodeWidthStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// 按列对齐的文本表格
+    Table,
+    /// 带表头的 CSV
+    Csv,
+}
+
+/// 报表中的一行，由统计查询的结果类型实现
+pub trait ReportRow {
+    /// 表头，与 `cells` 一一对应
+    const HEADERS: &'static [&'static str];
+
+    fn cells(&self) -> Vec<String>;
+}
+
+// #region write
+pub fn write_report<W: Write, R: ReportRow>(out: &mut W, rows: &[R], format: ReportFormat) -> Result<(), AppError> {
+    let rows: Vec<Vec<String>> = rows.iter().map(ReportRow::cells).collect();
+    match format {
+        ReportFormat::Table => write_table(out, R::HEADERS, &rows)?,
+        ReportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(&mut *out);
+            writer.write_record(R::HEADERS).map_err(io::Error::from)?;
+            for row in &rows {
+                writer.write_record(row).map_err(io::Error::from)?;
+            }
+            writer.flush()?;
+        }
+    }
+    Ok(())
+}
+// #endregion write
+
+/// 按显示宽度对齐，中文字符占两列
+fn write_table<W: Write>(out: &mut W, headers: &[&str], rows: &[Vec<String>]) -> io::Result<()> {
+    let mut widths: Vec<usize> = headers.iter().map(|header| header.width()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.width());
+        }
+    }
+
+    let write_row = |out: &mut W, cells: &mut dyn Iterator<Item = &str>| -> io::Result<()> {
+        let line: Vec<String> = cells
+            .zip(&widths)
+            .map(|(cell, width)| format!("{}{}", cell, " ".repeat(width - cell.width())))
+            .collect();
+        writeln!(out, "{}", line.join("  ").trim_end())
+    };
+
+    write_row(out, &mut headers.iter().copied())?;
+    writeln!(out, "{}", widths.iter().map(|width| "-".repeat(*width)).collect::<Vec<_>>().join("  "))?;
+    for row in rows {
+        write_row(out, &mut row.iter().map(String::as_str))?;
+    }
+    Ok(())
+}